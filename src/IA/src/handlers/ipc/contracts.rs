@@ -3,9 +3,45 @@ use alloc::vec::Vec;
 pub const IPC_VERSION: u16 = 1;
 pub const IPC_MAX_PAYLOAD_BYTES: usize = 1024;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+pub const IPC_SCHEMA_HEADER_LEN: usize = 2;
+
+/// Layout of exported payloads. V1 carries only the fixed counters; V2 adds
+/// the gauge section and is the current layout.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum IpcSchemaVersion {
 	V1,
+	V2,
+}
+
+impl IpcSchemaVersion {
+	pub const LATEST: IpcSchemaVersion = IpcSchemaVersion::V2;
+
+	pub fn as_u16(self) -> u16 {
+		match self {
+			IpcSchemaVersion::V1 => 1,
+			IpcSchemaVersion::V2 => 2,
+		}
+	}
+
+	pub fn from_u16(value: u16) -> Option<Self> {
+		match value {
+			1 => Some(IpcSchemaVersion::V1),
+			2 => Some(IpcSchemaVersion::V2),
+			_ => None,
+		}
+	}
+
+	pub fn header(self) -> [u8; IPC_SCHEMA_HEADER_LEN] {
+		self.as_u16().to_le_bytes()
+	}
+
+	pub fn parse_header(payload: &[u8]) -> Option<(Self, &[u8])> {
+		if payload.len() < IPC_SCHEMA_HEADER_LEN {
+			return None;
+		}
+		let version = u16::from_le_bytes([payload[0], payload[1]]);
+		Self::from_u16(version).map(|v| (v, &payload[IPC_SCHEMA_HEADER_LEN..]))
+	}
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -2,19 +2,52 @@ use alloc::vec::Vec;
 use crate::utils::observability;
 use crate::init::{is_locked, set_locked};
 use crate::security::tls::bundle as tls_bundle;
+use super::contracts::IpcSchemaVersion;
 
 pub const OP_EXPORT_METRICS: u16 = 9000;
 pub const OP_EXPORT_HEALTH: u16 = 9001;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExportError {
+	Locked,
+	UnknownOpcode,
+	UnsupportedVersion(u16),
+}
+
 pub fn handle_export(opcode: u16) -> Option<Vec<u8>> {
+	handle_export_version(opcode, IpcSchemaVersion::LATEST.as_u16()).ok()
+}
+
+/// Like `handle_export`, but a metrics export carries only the listed keys,
+/// in request order; unknown keys are omitted and an empty list exports
+/// everything. Health exports ignore `keys`.
+pub fn handle_export_projected(opcode: u16, keys: &[&str]) -> Option<Vec<u8>> {
+	export(opcode, IpcSchemaVersion::LATEST.as_u16(), keys).ok()
+}
+
+/// Exports in the requested schema layout. The payload starts with the
+/// little-endian schema version so consumers can tell which layout follows.
+pub fn handle_export_version(opcode: u16, version: u16) -> Result<Vec<u8>, ExportError> {
+	export(opcode, version, &[])
+}
+
+fn export(opcode: u16, version: u16, keys: &[&str]) -> Result<Vec<u8>, ExportError> {
 	let now_ms = crate::time::now_ms();
 	if is_locked() || !tls_bundle::is_bundle_valid(now_ms) {
 		set_locked(true);
-		return None;
-	}
-	match opcode {
-		OP_EXPORT_METRICS => Some(observability::export_metrics().into_bytes()),
-		OP_EXPORT_HEALTH => Some(observability::export_health().into_bytes()),
-		_ => None,
+		return Err(ExportError::Locked);
 	}
+	let schema = IpcSchemaVersion::from_u16(version).ok_or(ExportError::UnsupportedVersion(version))?;
+	let core_only = schema == IpcSchemaVersion::V1;
+	let body = match opcode {
+		OP_EXPORT_METRICS if !keys.is_empty() => observability::export_projected(keys, core_only),
+		OP_EXPORT_METRICS if core_only => observability::export_core_metrics(),
+		OP_EXPORT_METRICS => observability::export_metrics(),
+		OP_EXPORT_HEALTH => observability::export_health(),
+		_ => return Err(ExportError::UnknownOpcode),
+	};
+	let mut out = Vec::with_capacity(body.len() + schema.header().len());
+	out.extend_from_slice(&schema.header());
+	out.extend_from_slice(body.as_bytes());
+	Ok(out)
 }
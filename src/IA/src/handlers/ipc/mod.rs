@@ -9,8 +9,9 @@ pub use contracts::{
 	IpcSchemaVersion,
 	IpcTargetClass,
 	IPC_MAX_PAYLOAD_BYTES,
+	IPC_SCHEMA_HEADER_LEN,
 	IPC_VERSION,
 };
 pub use router::{route, route_with_quota, set_channel_capabilities, set_channel_quota, set_channel_require_auth};
 pub use router::{module_auth_key, next_nonce_for_module, build_secure_message, route_with_module};
-pub use endpoints::{handle_export, OP_EXPORT_HEALTH, OP_EXPORT_METRICS};
+pub use endpoints::{handle_export, handle_export_projected, handle_export_version, ExportError, OP_EXPORT_HEALTH, OP_EXPORT_METRICS};
@@ -42,6 +42,52 @@ pub mod ml {
     pub struct VoiceModel;
     pub struct FingerprintModel;
 
+    pub mod preprocess {
+        /// Scales `values` to unit L2 norm. An all-zero vector is left as is.
+        pub fn normalize_l2(values: &mut [f32]) {
+            let norm = libm::sqrtf(values.iter().map(|v| v * v).sum::<f32>());
+            if norm <= f32::EPSILON {
+                return;
+            }
+            for v in values.iter_mut() {
+                *v /= norm;
+            }
+        }
+
+        /// Linearly maps the range of `values` onto `[min, max]`. Constant
+        /// input has no range to map, so every value becomes `min`.
+        pub fn min_max_scale(values: &mut [f32], min: f32, max: f32) {
+            if values.is_empty() {
+                return;
+            }
+            let lo = values.iter().copied().fold(f32::INFINITY, f32::min);
+            let hi = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            let range = hi - lo;
+            for v in values.iter_mut() {
+                *v = if range <= f32::EPSILON {
+                    min
+                } else {
+                    min + (*v - lo) / range * (max - min)
+                };
+            }
+        }
+
+        /// Standardises `values` to zero mean and unit variance. Constant
+        /// input has zero variance and is mapped to all zeros.
+        pub fn zscore(values: &mut [f32]) {
+            if values.is_empty() {
+                return;
+            }
+            let n = values.len() as f32;
+            let mean = values.iter().sum::<f32>() / n;
+            let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f32>() / n;
+            let std_dev = libm::sqrtf(variance);
+            for v in values.iter_mut() {
+                *v = if std_dev <= f32::EPSILON { 0.0 } else { (*v - mean) / std_dev };
+            }
+        }
+    }
+
     fn cosine_similarity_bytes(a: &[u8], b: &[u8]) -> f32 {
         let len = core::cmp::min(a.len(), b.len());
         if len == 0 {
@@ -52,9 +98,9 @@ pub mod ml {
         let mut norm_a = 0.0f32;
         let mut norm_b = 0.0f32;
 
-        for i in 0..len {
-            let fa = a[i] as f32 / 255.0;
-            let fb = b[i] as f32 / 255.0;
+        for (&x, &y) in a[..len].iter().zip(b[..len].iter()) {
+            let fa = x as f32 / 255.0;
+            let fb = y as f32 / 255.0;
             dot += fa * fb;
             norm_a += fa * fa;
             norm_b += fb * fb;
@@ -64,10 +110,82 @@ pub mod ml {
             return 0.0;
         }
 
-        let denom = norm_a.sqrt() * norm_b.sqrt();
+        let denom = libm::sqrtf(norm_a) * libm::sqrtf(norm_b);
         (dot / denom).clamp(0.0, 1.0)
     }
 
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct ThresholdCalibration {
+        pub threshold: f32,
+        pub eer: f32,
+    }
+
+    /// Picks the match threshold where the false-reject rate on
+    /// `genuine_scores` and the false-accept rate on `impostor_scores` are
+    /// closest, i.e. the equal-error point. Candidates are the observed
+    /// scores; the chosen one is moved halfway down to the next lower score,
+    /// which leaves both error rates unchanged. Returns `None` if either set
+    /// is empty.
+    pub fn calibrate_threshold(genuine_scores: &[f32], impostor_scores: &[f32]) -> Option<ThresholdCalibration> {
+        if genuine_scores.is_empty() || impostor_scores.is_empty() {
+            return None;
+        }
+        let error_rates = |t: f32| {
+            let rejected = genuine_scores.iter().filter(|&&s| s < t).count();
+            let accepted = impostor_scores.iter().filter(|&&s| s >= t).count();
+            (
+                rejected as f32 / genuine_scores.len() as f32,
+                accepted as f32 / impostor_scores.len() as f32,
+            )
+        };
+
+        let mut best: Option<(f32, f32, f32)> = None;
+        for &candidate in genuine_scores.iter().chain(impostor_scores.iter()) {
+            let (frr, far) = error_rates(candidate);
+            let gap = (frr - far).abs();
+            let worst = frr.max(far);
+            let better = match best {
+                None => true,
+                Some((_, best_gap, best_worst)) => gap < best_gap || (gap == best_gap && worst < best_worst),
+            };
+            if better {
+                best = Some((candidate, gap, worst));
+            }
+        }
+
+        let (candidate, _, _) = best?;
+        let (frr, far) = error_rates(candidate);
+        let lower = genuine_scores
+            .iter()
+            .chain(impostor_scores.iter())
+            .copied()
+            .filter(|&s| s < candidate)
+            .fold(None, |acc: Option<f32>, s| Some(acc.map_or(s, |a| a.max(s))));
+        let threshold = match lower {
+            Some(lower) => (lower + candidate) / 2.0,
+            None => candidate,
+        };
+        Some(ThresholdCalibration { threshold, eer: (frr + far) / 2.0 })
+    }
+
+    /// Scores an already-extracted feature vector of per-dimension match
+    /// strengths in `[0, 1]`. Pure, so the match boundary can be tested
+    /// without sampling hardware.
+    fn score_features(features: &[f32]) -> f32 {
+        let mut sum = 0.0f32;
+        let mut count = 0u32;
+        for &value in features {
+            if value.is_finite() {
+                sum += value.clamp(0.0, 1.0);
+                count += 1;
+            }
+        }
+        if count == 0 {
+            return 0.0;
+        }
+        sum / count as f32
+    }
+
     impl FaceModel {
         pub fn new() -> Self {
             FaceModel
@@ -76,6 +194,14 @@ pub mod ml {
         pub fn similarity(&self, _a: &[u8], _b: &[u8]) -> f32 {
             cosine_similarity_bytes(_a, _b)
         }
+
+        pub fn score(&self, features: &[f32]) -> f32 {
+            score_features(features)
+        }
+
+        pub fn decide(&self, features: &[f32], threshold: f32) -> bool {
+            self.score(features) >= threshold
+        }
     }
 
     impl VoiceModel {
@@ -86,6 +212,14 @@ pub mod ml {
         pub fn similarity(&self, _a: &[u8], _b: &[u8]) -> f32 {
             cosine_similarity_bytes(_a, _b)
         }
+
+        pub fn score(&self, features: &[f32]) -> f32 {
+            score_features(features)
+        }
+
+        pub fn decide(&self, features: &[f32], threshold: f32) -> bool {
+            self.score(features) >= threshold
+        }
     }
 
     impl FingerprintModel {
@@ -96,6 +230,14 @@ pub mod ml {
         pub fn similarity(&self, _a: &[u8], _b: &[u8]) -> f32 {
             cosine_similarity_bytes(_a, _b)
         }
+
+        pub fn score(&self, features: &[f32]) -> f32 {
+            score_features(features)
+        }
+
+        pub fn decide(&self, features: &[f32], threshold: f32) -> bool {
+            self.score(features) >= threshold
+        }
     }
 }
 #[path = "security/loop/mod.rs"]
@@ -15,6 +15,9 @@ use crate::r#loop::module_loop::ModuleLoop;
 use crate::modules::runtime::GlobalRuntimeServices;
 use crate::utils::observability;
 use crate::init::with_cache_api;
+use crate::time;
+
+pub const LOOP_NAMES: [&str; 6] = ["primary", "secondary", "thirth", "external", "utility", "module"];
 
 #[derive(Clone, Copy)]
 pub struct LoopState {
@@ -44,6 +47,7 @@ pub struct LoopManager {
     module_loop: ModuleLoop<GlobalRuntimeServices>,
     state: Mutex<LoopState>,
     profiling: Mutex<LoopProfiling>,
+    loop_profiling: Mutex<[LoopProfiling; LOOP_NAMES.len()]>,
     observability: Mutex<LoopObservability>,
 }
 
@@ -58,6 +62,7 @@ impl LoopManager {
             module_loop: ModuleLoop::new(GlobalRuntimeServices::new()),
             state: Mutex::new(LoopState::new()),
             profiling: Mutex::new(LoopProfiling::new()),
+            loop_profiling: Mutex::new([LoopProfiling::new(); LOOP_NAMES.len()]),
             observability: Mutex::new(LoopObservability::new()),
         }
     }
@@ -71,12 +76,20 @@ impl LoopManager {
         global_state: &GlobalStateManager,
         bus: &crate::core::ipc_bus::IpcBus,
     ) {
+        let run_start = time::now_ms();
+        let mut start = run_start;
         self.primary_loop.run(timestamp_ms, orchestrator, pipeline);
+        start = self.finish_loop_run(0, start);
         self.secondary_loop.run(timestamp_ms, orchestrator, pipeline);
+        start = self.finish_loop_run(1, start);
         self.thirth_loop.run(timestamp_ms, tls);
+        start = self.finish_loop_run(2, start);
         self.external_loop.run(timestamp_ms, global_state);
+        start = self.finish_loop_run(3, start);
         self.utility_loop.run(timestamp_ms);
+        start = self.finish_loop_run(4, start);
         self.module_loop.run(timestamp_ms, bus);
+        let run_end = self.finish_loop_run(5, start);
 
         let mut state = self.state.lock();
         state.iterations += 1;
@@ -89,6 +102,7 @@ impl LoopManager {
             + self.module_loop.get_state().processed;
 
         let mut profiling = self.profiling.lock();
+        profiling.record_run(run_start, run_end);
         profiling.update(
             timestamp_ms,
             self.primary_loop.get_state().processed,
@@ -123,6 +137,33 @@ impl LoopManager {
         *self.profiling.lock()
     }
 
+    /// Per-loop profiling, keyed by the names in `LOOP_NAMES`.
+    pub fn profiling_for(&self, name: &str) -> Option<LoopProfiling> {
+        let idx = LOOP_NAMES.iter().position(|n| *n == name)?;
+        Some(self.loop_profiling.lock()[idx])
+    }
+
+    pub fn record_loop_run(&self, name: &str, start_ms: u64, end_ms: u64) -> bool {
+        match LOOP_NAMES.iter().position(|n| *n == name) {
+            Some(idx) => {
+                self.loop_profiling.lock()[idx].record_run(start_ms, end_ms);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn reset_profiling(&self) {
+        *self.profiling.lock() = LoopProfiling::new();
+        *self.loop_profiling.lock() = [LoopProfiling::new(); LOOP_NAMES.len()];
+    }
+
+    fn finish_loop_run(&self, idx: usize, start_ms: u64) -> u64 {
+        let end_ms = time::now_ms();
+        self.loop_profiling.lock()[idx].record_run(start_ms, end_ms);
+        end_ms
+    }
+
     pub fn export_profiling(&self) -> String {
         self.profiling.lock().export()
     }
@@ -166,6 +207,10 @@ pub struct LoopProfiling {
     pub external_processed: u32,
     pub utility_processed: u32,
     pub ema_alpha: f32,
+    pub runs: u64,
+    pub total_run_ms: u64,
+    pub max_run_ms: u64,
+    pub last_run_ms: u64,
     interval_window: [f32; 64],
     window_idx: usize,
     window_len: usize,
@@ -215,6 +260,10 @@ impl LoopProfiling {
             external_processed: 0,
             utility_processed: 0,
             ema_alpha: 0.1,
+            runs: 0,
+            total_run_ms: 0,
+            max_run_ms: 0,
+            last_run_ms: 0,
             interval_window: [0.0; 64],
             window_idx: 0,
             window_len: 0,
@@ -262,6 +311,14 @@ impl LoopProfiling {
         self.utility_processed = utility_processed;
     }
 
+    pub fn record_run(&mut self, start_ms: u64, end_ms: u64) {
+        let duration = end_ms.saturating_sub(start_ms);
+        self.runs = self.runs.saturating_add(1);
+        self.total_run_ms = self.total_run_ms.saturating_add(duration);
+        self.max_run_ms = self.max_run_ms.max(duration);
+        self.last_run_ms = start_ms;
+    }
+
     pub fn export(&self) -> String {
         alloc::format!(
             "tick_avg_ms={:.2}, primary={}, secondary={}, thirth={}, external={}, utility={}",
@@ -7,8 +7,8 @@ pub mod external_loop;
 pub mod utility_loop;
 pub mod module_loop;
 
-pub use loop_manager::{LoopManager, LoopState, LoopProfiling};
-pub use pipeline_executor::{PipelineExecutor, PipelineMetrics, PipelineStage, PipelineTask};
+pub use loop_manager::{LoopManager, LoopState, LoopProfiling, LOOP_NAMES};
+pub use pipeline_executor::{PipelineExecutor, PipelineMetrics, PipelineStage, PipelineTask, StageTimeoutPolicy, BackpressurePolicy};
 pub use primary_loop::PrimaryLoop;
 pub use secondary_loop::SecondaryLoop;
 pub use secondary_loop::LoopDiagnostics;
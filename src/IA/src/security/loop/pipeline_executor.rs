@@ -20,6 +20,24 @@ pub struct PipelineTask {
     pub task_id: u32,
     pub context_id: ContextId,
     pub state: ExecutionState,
+    pub started_at_ms: u64,
+}
+
+/// What happens to a task whose stage runs past its `max_duration_ms`:
+/// `Skip` moves it on to the next stage, `Fail` drops it from the pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageTimeoutPolicy {
+    Skip,
+    Fail,
+}
+
+/// What happens when a task is ready to move on but the next stage's queue
+/// is at capacity: `Block` leaves it pending where it is, `Shed` drops it and
+/// counts it in `dropped_tasks`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackpressurePolicy {
+    Block,
+    Shed,
 }
 
 #[derive(Debug, Clone)]
@@ -28,12 +46,19 @@ pub struct PipelineMetrics {
     pub total_failed: u32,
     pub average_latency_ms: u32,
     pub throughput: f32,
+    pub stage_timeouts: u32,
+    pub dropped_tasks: u32,
+    pub stage_queue_depths: Vec<(u32, u32)>,
 }
 
 pub struct PipelineExecutor {
     stages: Mutex<BTreeMap<u32, Vec<PipelineTask>>>,
     metrics: Mutex<PipelineMetrics>,
     task_counter: Mutex<u32>,
+    stage_budgets: Mutex<BTreeMap<u32, u64>>,
+    timeout_policy: Mutex<StageTimeoutPolicy>,
+    stage_capacities: Mutex<BTreeMap<u32, u32>>,
+    backpressure_policy: Mutex<BackpressurePolicy>,
 }
 
 impl PipelineExecutor {
@@ -45,12 +70,84 @@ impl PipelineExecutor {
                 total_failed: 0,
                 average_latency_ms: 0,
                 throughput: 0.0,
+                stage_timeouts: 0,
+                dropped_tasks: 0,
+                stage_queue_depths: Vec::new(),
             }),
             task_counter: Mutex::new(0),
+            stage_budgets: Mutex::new(BTreeMap::new()),
+            timeout_policy: Mutex::new(StageTimeoutPolicy::Fail),
+            stage_capacities: Mutex::new(BTreeMap::new()),
+            backpressure_policy: Mutex::new(BackpressurePolicy::Block),
+        }
+    }
+
+    /// Bounds the number of pending tasks queued at `stage`; `None` removes
+    /// the bound.
+    pub fn set_stage_capacity(&self, stage: u32, depth: Option<u32>) {
+        let mut capacities = self.stage_capacities.lock();
+        match depth {
+            Some(depth) => {
+                capacities.insert(stage, depth);
+            }
+            None => {
+                capacities.remove(&stage);
+            }
         }
     }
 
+    pub fn set_backpressure_policy(&self, policy: BackpressurePolicy) {
+        *self.backpressure_policy.lock() = policy;
+    }
+
+    pub fn stage_queue_depth(&self, stage: u32) -> u32 {
+        let stages = self.stages.lock();
+        Self::pending_depth(&stages, stage)
+    }
+
+    fn pending_depth(stages: &BTreeMap<u32, Vec<PipelineTask>>, stage: u32) -> u32 {
+        stages.get(&stage)
+            .map(|tasks| tasks.iter().filter(|t| t.state == ExecutionState::Pending).count() as u32)
+            .unwrap_or(0)
+    }
+
+    fn stage_full(&self, stages: &BTreeMap<u32, Vec<PipelineTask>>, stage: u32) -> bool {
+        match self.stage_capacities.lock().get(&stage) {
+            Some(&capacity) => Self::pending_depth(stages, stage) >= capacity,
+            None => false,
+        }
+    }
+
+    fn record_dropped(&self) {
+        let mut metrics = self.metrics.lock();
+        metrics.dropped_tasks = metrics.dropped_tasks.saturating_add(1);
+    }
+
+    pub fn set_stage_timeout(&self, stage: u32, max_duration_ms: Option<u64>) {
+        let mut budgets = self.stage_budgets.lock();
+        match max_duration_ms {
+            Some(limit) => {
+                budgets.insert(stage, limit);
+            }
+            None => {
+                budgets.remove(&stage);
+            }
+        }
+    }
+
+    pub fn stage_timeout(&self, stage: u32) -> Option<u64> {
+        self.stage_budgets.lock().get(&stage).copied()
+    }
+
+    pub fn set_timeout_policy(&self, policy: StageTimeoutPolicy) {
+        *self.timeout_policy.lock() = policy;
+    }
+
     pub fn create_pipeline(&self, context_id: ContextId) -> u32 {
+        self.create_pipeline_at(context_id, crate::time::now_ms())
+    }
+
+    pub fn create_pipeline_at(&self, context_id: ContextId, now_ms: u64) -> u32 {
         let mut counter = self.task_counter.lock();
         let task_id = *counter;
         *counter += 1;
@@ -62,6 +159,7 @@ impl PipelineExecutor {
             task_id,
             context_id,
             state: ExecutionState::Pending,
+            started_at_ms: now_ms,
         };
 
         stages.entry(0).or_insert_with(Vec::new).push(input_task);
@@ -70,44 +168,137 @@ impl PipelineExecutor {
     }
 
     pub fn progress_task(&self, task_id: u32, current_stage: u32) -> Option<u32> {
+        self.progress_task_at(task_id, current_stage, crate::time::now_ms())
+    }
+
+    pub fn progress_task_at(&self, task_id: u32, current_stage: u32, now_ms: u64) -> Option<u32> {
         let mut stages = self.stages.lock();
 
-        let mut next_stage = None;
+        let next_full = current_stage < 6 && self.stage_full(&stages, current_stage + 1);
+        let policy = *self.backpressure_policy.lock();
+        let mut found = false;
 
         if let Some(tasks) = stages.get_mut(&current_stage) {
             for task in tasks.iter_mut() {
                 if task.task_id == task_id {
+                    if next_full {
+                        if policy == BackpressurePolicy::Block {
+                            return None;
+                        }
+                        task.state = ExecutionState::Failed;
+                        self.record_dropped();
+                        return None;
+                    }
                     task.state = ExecutionState::Completed;
-                    next_stage = Some(current_stage + 1);
+                    found = true;
                     break;
                 }
             }
         }
 
-        if let Some(ns) = next_stage {
-            if ns <= 6 {
-                let new_task = PipelineTask {
-                    stage: match ns {
-                        0 => PipelineStage::Input,
-                        1 => PipelineStage::Preprocessing,
-                        2 => PipelineStage::Analysis,
-                        3 => PipelineStage::Learning,
-                        4 => PipelineStage::Decision,
-                        5 => PipelineStage::Action,
-                        _ => PipelineStage::Output,
-                    },
-                    task_id,
-                    context_id: 0,
-                    state: ExecutionState::Pending,
-                };
-
-                stages.entry(ns).or_insert_with(Vec::new).push(new_task);
-                Some(ns)
-            } else {
-                self.finalize_task(task_id);
-                None
+        if found {
+            self.advance_task(&mut stages, task_id, current_stage, now_ms)
+        } else {
+            None
+        }
+    }
+
+    /// Aborts every pending task that has spent longer than its stage's
+    /// budget, applying the configured `StageTimeoutPolicy`. A task that
+    /// would be skipped into a full stage stays pending under
+    /// `BackpressurePolicy::Block` and is only shed under `Shed`. Returns the
+    /// number of tasks aborted.
+    pub fn abort_timed_out(&self, now_ms: u64) -> u32 {
+        let budgets = self.stage_budgets.lock().clone();
+        if budgets.is_empty() {
+            return 0;
+        }
+        let policy = *self.timeout_policy.lock();
+        let mut stages = self.stages.lock();
+
+        let mut expired = Vec::new();
+        for (&stage, tasks) in stages.iter_mut() {
+            let Some(&limit) = budgets.get(&stage) else {
+                continue;
+            };
+            for task in tasks.iter_mut() {
+                if task.state == ExecutionState::Pending
+                    && now_ms.saturating_sub(task.started_at_ms) > limit
+                {
+                    task.state = ExecutionState::Failed;
+                    expired.push((task.task_id, stage));
+                }
             }
+        }
+
+        let backpressure = *self.backpressure_policy.lock();
+        let mut aborted = 0u32;
+        for &(task_id, stage) in expired.iter() {
+            match policy {
+                StageTimeoutPolicy::Skip if stage < 6 && self.stage_full(&stages, stage + 1) => {
+                    if backpressure == BackpressurePolicy::Block {
+                        Self::set_state(&mut stages, task_id, stage, ExecutionState::Pending);
+                        continue;
+                    }
+                    self.record_dropped();
+                }
+                StageTimeoutPolicy::Skip => {
+                    let _ = self.advance_task(&mut stages, task_id, stage, now_ms);
+                }
+                StageTimeoutPolicy::Fail => self.fail_task(task_id),
+            }
+            aborted += 1;
+        }
+
+        if aborted > 0 {
+            let mut metrics = self.metrics.lock();
+            metrics.stage_timeouts = metrics.stage_timeouts.saturating_add(aborted);
+        }
+        aborted
+    }
+
+    fn set_state(
+        stages: &mut BTreeMap<u32, Vec<PipelineTask>>,
+        task_id: u32,
+        stage: u32,
+        state: ExecutionState,
+    ) {
+        if let Some(task) = stages.get_mut(&stage)
+            .and_then(|tasks| tasks.iter_mut().find(|t| t.task_id == task_id))
+        {
+            task.state = state;
+        }
+    }
+
+    fn advance_task(
+        &self,
+        stages: &mut BTreeMap<u32, Vec<PipelineTask>>,
+        task_id: u32,
+        current_stage: u32,
+        now_ms: u64,
+    ) -> Option<u32> {
+        let ns = current_stage + 1;
+        if ns <= 6 {
+            let new_task = PipelineTask {
+                stage: match ns {
+                    0 => PipelineStage::Input,
+                    1 => PipelineStage::Preprocessing,
+                    2 => PipelineStage::Analysis,
+                    3 => PipelineStage::Learning,
+                    4 => PipelineStage::Decision,
+                    5 => PipelineStage::Action,
+                    _ => PipelineStage::Output,
+                },
+                task_id,
+                context_id: 0,
+                state: ExecutionState::Pending,
+                started_at_ms: now_ms,
+            };
+
+            stages.entry(ns).or_insert_with(Vec::new).push(new_task);
+            Some(ns)
         } else {
+            self.finalize_task(task_id);
             None
         }
     }
@@ -142,7 +333,15 @@ impl PipelineExecutor {
     }
 
     pub fn get_metrics(&self) -> PipelineMetrics {
-        self.metrics.lock().clone()
+        let depths = {
+            let stages = self.stages.lock();
+            stages.keys()
+                .map(|&stage| (stage, Self::pending_depth(&stages, stage)))
+                .collect()
+        };
+        let mut metrics = self.metrics.lock().clone();
+        metrics.stage_queue_depths = depths;
+        metrics
     }
 
     pub fn update_latency(&self, latency_ms: u32) {
@@ -174,12 +373,14 @@ impl PipelineExecutor {
     }
 
     pub fn parallel_process_stage(&self, stage: u32, batch_size: u32) -> u32 {
+        self.abort_timed_out(crate::time::now_ms());
         let pending = self.get_pending_at_stage(stage);
         let mut processed = 0;
 
         for task in pending.iter().take(batch_size as usize) {
-            self.progress_task(task.task_id, stage);
-            processed += 1;
+            if self.progress_task(task.task_id, stage).is_some() {
+                processed += 1;
+            }
         }
 
         processed
@@ -33,3 +33,13 @@ pub fn now_ms() -> u64 {
 	}
 	FALLBACK_TICKS.fetch_add(1, Ordering::Relaxed)
 }
+
+/// Variante non bloquante pour les chemins de faute : `None` si le slot du
+/// timer est verrouillé.
+pub fn try_now_ms() -> Option<u64> {
+	let timer = *HW_TIMER_FN.try_lock()?;
+	Some(match timer {
+		Some(timer) => timer(),
+		None => FALLBACK_TICKS.fetch_add(1, Ordering::Relaxed),
+	})
+}
@@ -1,3 +1,3 @@
 pub mod hal;
 
-pub use hal::now_ms;
+pub use hal::{now_ms, try_now_ms};
@@ -1,4 +1,12 @@
 use crate::prelude::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+use spin::Mutex;
+use crate::io::codec;
+use crate::time;
+use crate::utils::trace_buffer;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorCode {
@@ -63,3 +71,237 @@ impl Error {
 
 pub type Result<T> = core::result::Result<T, Error>;
 pub type EngineError = Error;
+
+pub const FAULT_TRACE_RECORDS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaultContext {
+    pub component: String,
+    pub op: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaultSnapshot {
+    pub context: Option<FaultContext>,
+    pub reason: String,
+    pub timestamp_ms: u64,
+    pub recent_traces: Vec<String>,
+}
+
+impl FaultSnapshot {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let (component, op) = match &self.context {
+            Some(ctx) => (ctx.component.as_str(), ctx.op.as_str()),
+            None => ("", ""),
+        };
+        codec::encode_u32_le(self.context.is_some() as u32, &mut out);
+        codec::encode_with_len(component.as_bytes(), &mut out);
+        codec::encode_with_len(op.as_bytes(), &mut out);
+        codec::encode_with_len(self.reason.as_bytes(), &mut out);
+        codec::encode_u32_le(self.timestamp_ms as u32, &mut out);
+        codec::encode_u32_le((self.timestamp_ms >> 32) as u32, &mut out);
+        codec::encode_u32_le(self.recent_traces.len() as u32, &mut out);
+        for trace in self.recent_traces.iter() {
+            codec::encode_with_len(trace.as_bytes(), &mut out);
+        }
+        out
+    }
+
+    pub fn decode(input: &[u8]) -> Option<Self> {
+        let mut offset = 0;
+        let (has_context, used) = codec::decode_u32_le(&input[offset..])?;
+        offset += used;
+        let (component, used) = codec::decode_with_len(&input[offset..])?;
+        offset += used;
+        let (op, used) = codec::decode_with_len(&input[offset..])?;
+        offset += used;
+        let (reason, used) = codec::decode_with_len(&input[offset..])?;
+        offset += used;
+        let (ts_low, used) = codec::decode_u32_le(&input[offset..])?;
+        offset += used;
+        let (ts_high, used) = codec::decode_u32_le(&input[offset..])?;
+        offset += used;
+        let (count, used) = codec::decode_u32_le(&input[offset..])?;
+        offset += used;
+        let mut recent_traces = Vec::new();
+        for _ in 0..count {
+            let (trace, used) = codec::decode_with_len(&input[offset..])?;
+            offset += used;
+            recent_traces.push(String::from_utf8(trace).ok()?);
+        }
+        let context = if has_context != 0 {
+            Some(FaultContext {
+                component: String::from_utf8(component).ok()?,
+                op: String::from_utf8(op).ok()?,
+            })
+        } else {
+            None
+        };
+        Some(FaultSnapshot {
+            context,
+            reason: String::from_utf8(reason).ok()?,
+            timestamp_ms: ((ts_high as u64) << 32) | ts_low as u64,
+            recent_traces,
+        })
+    }
+}
+
+pub const FAULT_REGION_BYTES: usize = 2048;
+const FAULT_MAGIC: u32 = 0xFA17_C0DE;
+const FAULT_HEADER_BYTES: usize = 8;
+/// Longest component/op/reason kept; longer strings are cut at a char
+/// boundary so the fixed fields always fit ahead of the traces.
+const FAULT_FIELD_MAX: usize = 256;
+
+struct FaultRegion(UnsafeCell<[u8; FAULT_REGION_BYTES]>);
+
+// Access is serialized by `FAULT_REGION_BUSY`.
+unsafe impl Sync for FaultRegion {}
+
+/// Snapshot left for the next boot: `[magic u32][len u32][payload]`, with the
+/// payload in `FaultSnapshot::encode` layout. The section is not zeroed at
+/// reset, so the magic tells a real record from leftover RAM.
+#[cfg_attr(target_os = "none", link_section = ".noinit.fault_region")]
+static FAULT_REGION: FaultRegion = FaultRegion(UnsafeCell::new([0; FAULT_REGION_BYTES]));
+static FAULT_REGION_BUSY: AtomicBool = AtomicBool::new(false);
+
+static FAULT_CONTEXT: Mutex<Option<FaultContext>> = Mutex::new(None);
+
+/// Bounded writer over the region payload; nothing past the end is written.
+struct RegionWriter<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl RegionWriter<'_> {
+    fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    fn put_u32(&mut self, value: u32) {
+        self.put_raw(&value.to_le_bytes());
+    }
+
+    fn put_raw(&mut self, bytes: &[u8]) {
+        let n = bytes.len().min(self.remaining());
+        self.buf[self.pos..self.pos + n].copy_from_slice(&bytes[..n]);
+        self.pos += n;
+    }
+
+    fn put_field(&mut self, text: &str) {
+        let text = truncate_at_char(text, FAULT_FIELD_MAX);
+        self.put_u32(text.len() as u32);
+        self.put_raw(text.as_bytes());
+    }
+
+    /// Writes `text` length-prefixed only if it fits whole.
+    fn try_put_field(&mut self, text: &str) -> bool {
+        if 4 + text.len() > self.remaining() {
+            return false;
+        }
+        self.put_u32(text.len() as u32);
+        self.put_raw(text.as_bytes());
+        true
+    }
+}
+
+fn truncate_at_char(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// Records which component/operation is running so a later fault can be
+/// attributed to it.
+pub fn set_fault_context(component: &str, op: &str) {
+    *FAULT_CONTEXT.lock() = Some(FaultContext {
+        component: component.to_string(),
+        op: op.to_string(),
+    });
+}
+
+pub fn clear_fault_context() {
+    *FAULT_CONTEXT.lock() = None;
+}
+
+/// Fault handler: writes the last fault context and the most recent trace
+/// records into the fault region. Safe on the panic path: it only
+/// `try_lock`s and never allocates. Anything it cannot lock right away is
+/// left out; returns false if the region itself was busy.
+pub fn record_fault(reason: &str) -> bool {
+    if FAULT_REGION_BUSY
+        .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+        .is_err()
+    {
+        return false;
+    }
+    // SAFETY: the busy flag gives this call exclusive access to the region.
+    let region = unsafe { &mut *FAULT_REGION.0.get() };
+    region[..4].copy_from_slice(&0u32.to_le_bytes());
+
+    let (header, payload) = region.split_at_mut(FAULT_HEADER_BYTES);
+    let mut out = RegionWriter { buf: payload, pos: 0 };
+    match FAULT_CONTEXT.try_lock().as_deref() {
+        Some(Some(ctx)) => {
+            out.put_u32(1);
+            out.put_field(&ctx.component);
+            out.put_field(&ctx.op);
+        }
+        _ => {
+            out.put_u32(0);
+            out.put_field("");
+            out.put_field("");
+        }
+    }
+    out.put_field(reason);
+    let timestamp_ms = time::try_now_ms().unwrap_or(0);
+    out.put_u32(timestamp_ms as u32);
+    out.put_u32((timestamp_ms >> 32) as u32);
+    let count_at = out.pos;
+    out.put_u32(0);
+    let mut count = 0u32;
+    let mut full = false;
+    trace_buffer::try_for_each_recent(FAULT_TRACE_RECORDS, &mut |trace| {
+        if !full && out.try_put_field(trace) {
+            count += 1;
+        } else {
+            full = true;
+        }
+    });
+    out.buf[count_at..count_at + 4].copy_from_slice(&count.to_le_bytes());
+    let len = out.pos as u32;
+
+    header[4..8].copy_from_slice(&len.to_le_bytes());
+    header[..4].copy_from_slice(&FAULT_MAGIC.to_le_bytes());
+    FAULT_REGION_BUSY.store(false, Ordering::Release);
+    true
+}
+
+/// Reads and clears the snapshot left by the previous fault, if any.
+pub fn take_last_fault() -> Option<FaultSnapshot> {
+    if FAULT_REGION_BUSY
+        .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+        .is_err()
+    {
+        return None;
+    }
+    // SAFETY: the busy flag gives this call exclusive access to the region.
+    let region = unsafe { &mut *FAULT_REGION.0.get() };
+    let magic = codec::decode_u32_le(&region[..4]).map(|(v, _)| v);
+    let len = codec::decode_u32_le(&region[4..8]).map(|(v, _)| v as usize);
+    let snapshot = match (magic, len) {
+        (Some(FAULT_MAGIC), Some(len)) if len <= FAULT_REGION_BYTES - FAULT_HEADER_BYTES => {
+            FaultSnapshot::decode(&region[FAULT_HEADER_BYTES..FAULT_HEADER_BYTES + len])
+        }
+        _ => None,
+    };
+    region[..4].copy_from_slice(&0u32.to_le_bytes());
+    FAULT_REGION_BUSY.store(false, Ordering::Release);
+    snapshot
+}
@@ -1,3 +1,67 @@
-pub fn read_file(_path: &str) {}
-pub fn write_file(_path: &str, _data: &[u8]) {}
+use alloc::vec::Vec;
+use sha2::{Sha256, Digest};
+use spin::Mutex;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileError {
+	NotFound,
+	ChecksumMismatch,
+	WriteFailed,
+}
+
+pub type StorageReadFn = fn(&str) -> Option<Vec<u8>>;
+pub type StorageWriteFn = fn(&str, &[u8]) -> bool;
+
+#[derive(Clone, Copy)]
+struct Storage {
+	read: StorageReadFn,
+	write: StorageWriteFn,
+}
+
+static STORAGE: Mutex<Option<Storage>> = Mutex::new(None);
+
+/// Registers the platform's storage backend. Until this is called every read
+/// reports the file as missing and every write fails.
+pub fn set_storage(read: StorageReadFn, write: StorageWriteFn) {
+	*STORAGE.lock() = Some(Storage { read, write });
+}
+
+pub fn read_file(path: &str) -> Option<Vec<u8>> {
+	let storage = (*STORAGE.lock())?;
+	(storage.read)(path)
+}
+
+pub fn write_file(path: &str, data: &[u8]) -> bool {
+	match *STORAGE.lock() {
+		Some(storage) => (storage.write)(path, data),
+		None => false,
+	}
+}
+
+pub fn checksum(data: &[u8]) -> [u8; 32] {
+	Sha256::digest(data).into()
+}
+
+/// Checks `data` against its expected SHA-256 so corrupt model weights or
+/// configs are rejected before they reach the models.
+pub fn verify_checksum(data: &[u8], expected: &[u8; 32]) -> Result<(), FileError> {
+	if checksum(data) != *expected {
+		return Err(FileError::ChecksumMismatch);
+	}
+	Ok(())
+}
+
+/// Reads `path` and checks its SHA-256 against `expected`.
+pub fn read_with_checksum(path: &str, expected: &[u8; 32]) -> Result<Vec<u8>, FileError> {
+	let data = read_file(path).ok_or(FileError::NotFound)?;
+	verify_checksum(&data, expected)?;
+	Ok(data)
+}
+
+/// Writes `path` and returns the SHA-256 to pass to `read_with_checksum`.
+pub fn write_with_checksum(path: &str, data: &[u8]) -> Result<[u8; 32], FileError> {
+	if !write_file(path, data) {
+		return Err(FileError::WriteFailed);
+	}
+	Ok(checksum(data))
+}
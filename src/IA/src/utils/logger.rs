@@ -1,14 +1,29 @@
 use alloc::collections::VecDeque;
 use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use spin::{Mutex, Once};
 use crate::time;
 use crate::utils::error::ErrorCode;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum LogLevel {
-	Error,
-	Warn,
+	Trace,
+	Debug,
 	Info,
+	Warn,
+	Error,
+}
+
+impl LogLevel {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			LogLevel::Trace => "trace",
+			LogLevel::Debug => "debug",
+			LogLevel::Info => "info",
+			LogLevel::Warn => "warn",
+			LogLevel::Error => "error",
+		}
+	}
 }
 
 #[derive(Clone)]
@@ -18,11 +33,13 @@ pub struct LogEntry {
 	pub code: ErrorCode,
 	pub timestamp_ms: u64,
 	pub context: String,
+	pub fields: Vec<(String, String)>,
 }
 
 pub struct Logger {
 	entries: VecDeque<LogEntry>,
 	max_entries: usize,
+	min_level: LogLevel,
 }
 
 impl Logger {
@@ -30,10 +47,44 @@ impl Logger {
 		Logger {
 			entries: VecDeque::new(),
 			max_entries: 256,
+			min_level: LogLevel::Info,
 		}
 	}
 
+	pub fn set_min_level(&mut self, level: LogLevel) {
+		self.min_level = level;
+	}
+
+	pub fn min_level(&self) -> LogLevel {
+		self.min_level
+	}
+
+	pub fn enabled(&self, level: LogLevel) -> bool {
+		level >= self.min_level
+	}
+
 	pub fn log(&mut self, level: LogLevel, module: &str, code: ErrorCode, context: &str) {
+		self.log_fields(level, module, code, context, &[]);
+	}
+
+	/// Like `log`, with structured `k=v` fields attached. Fields are stored
+	/// sorted by key so the export is stable whatever the call-site order.
+	pub fn log_fields(
+		&mut self,
+		level: LogLevel,
+		module: &str,
+		code: ErrorCode,
+		context: &str,
+		fields: &[(&str, &str)],
+	) {
+		if !self.enabled(level) {
+			return;
+		}
+		let mut fields: Vec<(String, String)> = fields
+			.iter()
+			.map(|(k, v)| (k.to_string(), v.to_string()))
+			.collect();
+		fields.sort_by(|a, b| a.0.cmp(&b.0));
 		if self.entries.len() >= self.max_entries {
 			self.entries.pop_front();
 		}
@@ -43,21 +94,17 @@ impl Logger {
 			code,
 			timestamp_ms: time::now_ms(),
 			context: context.to_string(),
+			fields,
 		});
 	}
 
 	pub fn export_kv(&self) -> String {
 		let mut out = String::new();
 		for (idx, entry) in self.entries.iter().enumerate() {
-			let level = match entry.level {
-				LogLevel::Error => "error",
-				LogLevel::Warn => "warn",
-				LogLevel::Info => "info",
-			};
 			out.push_str(&alloc::format!(
-				"log{}.level={},log{}.module={},log{}.code={},log{}.ts={},log{}.ctx={};",
+				"log{}.level={},log{}.module={},log{}.code={},log{}.ts={},log{}.ctx={}",
 				idx,
-				level,
+				entry.level.as_str(),
 				idx,
 				entry.module,
 				idx,
@@ -67,6 +114,10 @@ impl Logger {
 				idx,
 				entry.context
 			));
+			for (key, value) in entry.fields.iter() {
+				out.push_str(&alloc::format!(",log{}.{}={}", idx, key, value));
+			}
+			out.push(';');
 		}
 		out
 	}
@@ -78,6 +129,22 @@ fn logger() -> &'static Mutex<Logger> {
 	LOGGER.call_once(|| Mutex::new(Logger::new()))
 }
 
+pub fn set_min_level(level: LogLevel) {
+	logger().lock().set_min_level(level);
+}
+
+pub fn min_level() -> LogLevel {
+	logger().lock().min_level()
+}
+
+pub fn enabled(level: LogLevel) -> bool {
+	logger().lock().enabled(level)
+}
+
+pub fn log_fields(level: LogLevel, module: &str, code: ErrorCode, context: &str, fields: &[(&str, &str)]) {
+	logger().lock().log_fields(level, module, code, context, fields);
+}
+
 pub fn error(module: &str, code: ErrorCode, context: &str) {
 	logger().lock().log(LogLevel::Error, module, code, context);
 }
@@ -90,6 +157,31 @@ pub fn info(module: &str, code: ErrorCode, context: &str) {
 	logger().lock().log(LogLevel::Info, module, code, context);
 }
 
+pub fn debug(module: &str, code: ErrorCode, context: &str) {
+	logger().lock().log(LogLevel::Debug, module, code, context);
+}
+
+pub fn trace(module: &str, code: ErrorCode, context: &str) {
+	logger().lock().log(LogLevel::Trace, module, code, context);
+}
+
+/// `ia_log!(level, module, code, context, "key" => value, ...)`. Skips
+/// formatting the field values entirely when `level` is filtered out.
+#[macro_export]
+macro_rules! ia_log {
+	($level:expr, $module:expr, $code:expr, $context:expr $(, $key:expr => $value:expr)* $(,)?) => {
+		if $crate::utils::logger::enabled($level) {
+			$crate::utils::logger::log_fields(
+				$level,
+				$module,
+				$code,
+				$context,
+				&[$(($key, &*$crate::prelude::ToString::to_string(&$value))),*],
+			);
+		}
+	};
+}
+
 pub fn export_logs() -> String {
 	logger().lock().export_kv()
 }
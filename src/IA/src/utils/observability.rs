@@ -1,6 +1,9 @@
 use alloc::collections::BTreeMap;
 use alloc::string::{String, ToString};
 use spin::{Mutex, Once};
+use crate::time;
+use crate::utils::error::ErrorCode;
+use crate::utils::logger::{self, LogLevel};
 
 #[derive(Clone, Copy, Default)]
 struct TimerStats {
@@ -39,6 +42,24 @@ impl MetricsRegistry {
 	}
 
 	pub fn export_metrics(&self) -> String {
+		let mut out = self.export_core_metrics();
+		if !self.gauges.is_empty() {
+			out.push_str(",gauges=");
+			let mut first = true;
+			for (key, value) in self.gauges.iter() {
+				if !first {
+					out.push(';');
+				}
+				first = false;
+				out.push_str(key);
+				out.push('=');
+				out.push_str(&value.to_string());
+			}
+		}
+		out
+	}
+
+	pub fn export_core_metrics(&self) -> String {
 		let mut out = String::new();
 		let ticks = self.counters.get("ticks").cloned().unwrap_or(0);
 		let errors = self.counters.get("errors_total").cloned().unwrap_or(0);
@@ -63,22 +84,47 @@ impl MetricsRegistry {
 			ipc_drops,
 			quota_throttles
 		));
-		if !self.gauges.is_empty() {
-			out.push_str(",gauges=");
-			let mut first = true;
-			for (key, value) in self.gauges.iter() {
-				if !first {
-					out.push(';');
-				}
-				first = false;
-				out.push_str(key);
-				out.push('=');
-				out.push_str(&value.to_string());
+		out
+	}
+
+	/// Exports only the requested metric keys, in request order. Keys that
+	/// are neither a core field nor a known gauge are silently omitted; with
+	/// `core_only` set, gauges are not considered known.
+	pub fn export_projected(&self, keys: &[&str], core_only: bool) -> String {
+		let mut out = String::new();
+		for key in keys {
+			let value = match self.core_value(key) {
+				Some(value) => value,
+				None if !core_only => match self.gauges.get(*key) {
+					Some(value) => value.to_string(),
+					None => continue,
+				},
+				None => continue,
+			};
+			if !out.is_empty() {
+				out.push(',');
 			}
+			out.push_str(key);
+			out.push('=');
+			out.push_str(&value);
 		}
 		out
 	}
 
+	fn core_value(&self, key: &str) -> Option<String> {
+		match key {
+			"ticks" | "errors_total" | "safe_ai_actions" | "ipc_drops" | "quota_throttles" => {
+				Some(self.counters.get(key).cloned().unwrap_or(0).to_string())
+			}
+			"latence_moy_ms" => Some(alloc::format!("{:.2}", self.avg_timer_ms(key))),
+			"latence_boucle_ms"
+			| "latence_boucle_p95_ms"
+			| "latence_boucle_p99_ms"
+			| "latence_boucle_jitter_ms" => Some(self.gauges.get(key).cloned().unwrap_or(0).to_string()),
+			_ => None,
+		}
+	}
+
 	pub fn export_health(&self) -> String {
 		let errors = self.counters.get("errors_total").cloned().unwrap_or(0);
 		let status = if errors > 0 { "degraded" } else { "ok" };
@@ -99,6 +145,112 @@ impl MetricsRegistry {
 	}
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RateDecision {
+	Emit { suppressed: u32 },
+	Suppress,
+}
+
+/// Upper bound on distinct keys a `RateLimitedLogger` tracks at once.
+pub const MAX_RATE_KEYS: usize = 64;
+
+#[derive(Clone, Copy)]
+struct RateWindow {
+	start_ms: u64,
+	emitted: u32,
+	suppressed: u32,
+}
+
+/// Lets at most `max_per_window` messages with the same key through per
+/// `window_ms`. Repeats beyond that are counted, and the first message let
+/// through after the window rolls over carries a `(suppressed M times)`
+/// summary. At most `MAX_RATE_KEYS` windows are kept: expired ones with no
+/// pending summary are pruned first, then the oldest is evicted.
+pub struct RateLimitedLogger {
+	max_per_window: u32,
+	window_ms: u64,
+	windows: BTreeMap<String, RateWindow>,
+}
+
+impl RateLimitedLogger {
+	pub fn new(max_per_window: u32, window_ms: u64) -> Self {
+		RateLimitedLogger {
+			max_per_window: max_per_window.max(1),
+			window_ms: window_ms.max(1),
+			windows: BTreeMap::new(),
+		}
+	}
+
+	pub fn check(&mut self, key: &str, now_ms: u64) -> RateDecision {
+		if !self.windows.contains_key(key) {
+			self.make_room(now_ms);
+		}
+		let window = self.windows.entry(key.to_string()).or_insert(RateWindow {
+			start_ms: now_ms,
+			emitted: 0,
+			suppressed: 0,
+		});
+		if now_ms.saturating_sub(window.start_ms) >= self.window_ms {
+			window.start_ms = now_ms;
+			window.emitted = 0;
+		}
+		if window.emitted >= self.max_per_window {
+			window.suppressed = window.suppressed.saturating_add(1);
+			return RateDecision::Suppress;
+		}
+		window.emitted += 1;
+		let suppressed = window.suppressed;
+		window.suppressed = 0;
+		RateDecision::Emit { suppressed }
+	}
+
+	pub fn tracked_keys(&self) -> usize {
+		self.windows.len()
+	}
+
+	fn make_room(&mut self, now_ms: u64) {
+		let window_ms = self.window_ms;
+		self.windows
+			.retain(|_, w| w.suppressed > 0 || now_ms.saturating_sub(w.start_ms) < window_ms);
+		if self.windows.len() < MAX_RATE_KEYS {
+			return;
+		}
+		let oldest = self.windows.iter().min_by_key(|(_, w)| w.start_ms).map(|(k, _)| k.clone());
+		if let Some(oldest) = oldest {
+			self.windows.remove(&oldest);
+		}
+	}
+
+	/// Logs `context` through the IA logger unless it is being rate limited.
+	/// Returns the text actually logged; `None` if rate limited or below the
+	/// logger's minimum level. Filtered messages do not use up the budget.
+	pub fn log_at(
+		&mut self,
+		level: LogLevel,
+		module: &str,
+		code: ErrorCode,
+		context: &str,
+		now_ms: u64,
+	) -> Option<String> {
+		if !logger::enabled(level) {
+			return None;
+		}
+		let text = match self.check(context, now_ms) {
+			RateDecision::Suppress => return None,
+			RateDecision::Emit { suppressed: 0 } => context.to_string(),
+			RateDecision::Emit { suppressed } => {
+				alloc::format!("{} (suppressed {} times)", context, suppressed)
+			}
+		};
+		logger::log_fields(level, module, code, &text, &[]);
+		Some(text)
+	}
+
+	pub fn log(&mut self, level: LogLevel, module: &str, code: ErrorCode, context: &str) -> Option<String> {
+		self.log_at(level, module, code, context, time::now_ms())
+	}
+}
+
 static METRICS: Once<Mutex<MetricsRegistry>> = Once::new();
 
 fn registry() -> &'static Mutex<MetricsRegistry> {
@@ -121,6 +273,14 @@ pub fn export_metrics() -> String {
 	registry().lock().export_metrics()
 }
 
+pub fn export_core_metrics() -> String {
+	registry().lock().export_core_metrics()
+}
+
+pub fn export_projected(keys: &[&str], core_only: bool) -> String {
+	registry().lock().export_projected(keys, core_only)
+}
+
 pub fn export_health() -> String {
 	registry().lock().export_health()
 }
@@ -2,10 +2,12 @@ use alloc::collections::VecDeque;
 use alloc::vec::Vec;
 use crate::prelude::String;
 use spin::{Mutex, Once};
+use crate::io::codec;
 
 pub struct TraceBuffer {
 	buffer: VecDeque<String>,
 	capacity: usize,
+	dropped: u64,
 }
 
 impl TraceBuffer {
@@ -13,12 +15,14 @@ impl TraceBuffer {
 		TraceBuffer {
 			buffer: VecDeque::with_capacity(capacity.max(1)),
 			capacity: capacity.max(1),
+			dropped: 0,
 		}
 	}
 
 	pub fn push(&mut self, entry: String) {
 		if self.buffer.len() >= self.capacity {
 			self.buffer.pop_front();
+			self.dropped = self.dropped.saturating_add(1);
 		}
 		self.buffer.push_back(entry);
 	}
@@ -27,6 +31,30 @@ impl TraceBuffer {
 		self.buffer.drain(..).collect()
 	}
 
+	/// Hands each record to `sink` as a length-prefixed frame, oldest first,
+	/// and empties the buffer. The overflow counter is left untouched.
+	pub fn drain_to(&mut self, sink: &mut dyn FnMut(&[u8])) {
+		let mut frame = Vec::new();
+		for entry in self.buffer.drain(..) {
+			frame.clear();
+			codec::encode_with_len(entry.as_bytes(), &mut frame);
+			sink(&frame);
+		}
+	}
+
+	/// Records evicted because the ring was full.
+	pub fn dropped(&self) -> u64 {
+		self.dropped
+	}
+
+	/// Visits the newest `limit` records, oldest first, without allocating.
+	pub fn for_each_recent(&self, limit: usize, f: &mut dyn FnMut(&str)) {
+		let skip = self.buffer.len().saturating_sub(limit);
+		for entry in self.buffer.iter().skip(skip) {
+			f(entry);
+		}
+	}
+
 	pub fn snapshot(&self) -> Vec<String> {
 		self.buffer.iter().cloned().collect()
 	}
@@ -50,6 +78,19 @@ pub fn export_trace() -> Vec<String> {
 	trace_buffer().lock().snapshot()
 }
 
+/// Fault-path variant of `export_trace`: never blocks, never allocates and
+/// never initializes the buffer. Returns false if the buffer is missing or
+/// currently locked.
+pub fn try_for_each_recent(limit: usize, f: &mut dyn FnMut(&str)) -> bool {
+	match TRACE.get().and_then(|buffer| buffer.try_lock()) {
+		Some(buffer) => {
+			buffer.for_each_recent(limit, f);
+			true
+		}
+		None => false,
+	}
+}
+
 pub fn drain_trace() -> Vec<String> {
 	trace_buffer().lock().drain()
 }
@@ -57,3 +98,11 @@ pub fn drain_trace() -> Vec<String> {
 pub fn trace_len() -> usize {
 	trace_buffer().lock().len()
 }
+
+pub fn drain_trace_to(sink: &mut dyn FnMut(&[u8])) {
+	trace_buffer().lock().drain_to(sink);
+}
+
+pub fn trace_dropped() -> u64 {
+	trace_buffer().lock().dropped()
+}
@@ -0,0 +1,46 @@
+mod test_guard;
+
+use redmi_ia::utils::error::{
+    clear_fault_context, record_fault, set_fault_context, take_last_fault, FaultContext, FaultSnapshot,
+    FAULT_REGION_BYTES, FAULT_TRACE_RECORDS,
+};
+use redmi_ia::utils::trace_buffer::trace_event;
+
+#[test]
+fn fault_snapshot_captures_context_and_recent_traces() {
+    for idx in 0..(FAULT_TRACE_RECORDS + 4) {
+        trace_event(format!("trace-{}", idx));
+    }
+    set_fault_context("secondary_loop", "verify_face");
+
+    assert!(record_fault("index out of bounds"));
+    let snapshot = take_last_fault().expect("persisted snapshot");
+    assert_eq!(
+        snapshot.context,
+        Some(FaultContext { component: "secondary_loop".into(), op: "verify_face".into() })
+    );
+    assert_eq!(snapshot.reason, "index out of bounds");
+    assert_eq!(snapshot.recent_traces.len(), FAULT_TRACE_RECORDS);
+    assert_eq!(snapshot.recent_traces.last().map(String::as_str), Some("trace-19"));
+    assert_eq!(snapshot.recent_traces.first().map(String::as_str), Some("trace-4"));
+    assert!(take_last_fault().is_none());
+
+    clear_fault_context();
+    let long_reason = "x".repeat(4 * FAULT_REGION_BYTES);
+    assert!(record_fault(&long_reason));
+    let snapshot = take_last_fault().expect("truncated snapshot");
+    assert_eq!(snapshot.context, None);
+    assert!(snapshot.reason.len() < FAULT_REGION_BYTES);
+}
+
+#[test]
+fn fault_snapshot_round_trips_without_context() {
+    let snapshot = FaultSnapshot {
+        context: None,
+        reason: "abort".into(),
+        timestamp_ms: (7u64 << 32) | 42,
+        recent_traces: vec!["a".into(), String::new()],
+    };
+    assert_eq!(FaultSnapshot::decode(&snapshot.encode()), Some(snapshot.clone()));
+    assert!(FaultSnapshot::decode(&snapshot.encode()[..5]).is_none());
+}
@@ -0,0 +1,45 @@
+mod test_guard;
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use redmi_ia::utils::file_ops::{
+	checksum, read_with_checksum, set_storage, verify_checksum, write_file, write_with_checksum, FileError,
+};
+
+static FILES: Mutex<BTreeMap<String, Vec<u8>>> = Mutex::new(BTreeMap::new());
+
+fn mem_read(path: &str) -> Option<Vec<u8>> {
+	FILES.lock().unwrap().get(path).cloned()
+}
+
+fn mem_write(path: &str, data: &[u8]) -> bool {
+	FILES.lock().unwrap().insert(path.to_string(), data.to_vec());
+	true
+}
+
+fn use_memory_storage() {
+	set_storage(mem_read, mem_write);
+}
+
+#[test]
+fn matching_checksum_returns_contents() {
+	use_memory_storage();
+	let digest = write_with_checksum("/models/face.bin", b"weights-v1").unwrap();
+	assert_eq!(read_with_checksum("/models/face.bin", &digest), Ok(b"weights-v1".to_vec()));
+}
+
+#[test]
+fn mismatched_checksum_is_rejected() {
+	use_memory_storage();
+	let digest = write_with_checksum("/config/ia.toml", b"threshold=0.8").unwrap();
+	assert!(write_file("/config/ia.toml", b"threshold=0.1"));
+	assert_eq!(read_with_checksum("/config/ia.toml", &digest), Err(FileError::ChecksumMismatch));
+	assert_eq!(verify_checksum(b"threshold=0.8", &digest), Ok(()));
+}
+
+#[test]
+fn missing_file_is_distinct_from_mismatch() {
+	use_memory_storage();
+	assert_eq!(read_with_checksum("/models/missing.bin", &checksum(b"")), Err(FileError::NotFound));
+}
@@ -0,0 +1,52 @@
+mod test_guard;
+use redmi_ia::handlers::ipc::{handle_export, handle_export_projected, OP_EXPORT_METRICS};
+use redmi_ia::init::set_locked;
+use redmi_ia::security::tls::bundle::{store_bundle, TlsBundle};
+use redmi_ia::utils::observability;
+
+fn unlock() {
+    set_locked(false);
+    store_bundle(TlsBundle {
+        ticket: "t".into(),
+        routes: Vec::new(),
+        expires_at_ms: 1_000_000,
+        generation: 1,
+    });
+}
+
+fn export_body(keys: &[&str]) -> String {
+    let payload = handle_export_projected(OP_EXPORT_METRICS, keys).expect("export");
+    String::from_utf8(payload[2..].to_vec()).unwrap()
+}
+
+#[test]
+fn export_projects_requested_subset() {
+    unlock();
+    observability::set_gauge("projection_probe", 42);
+    let body = export_body(&["projection_probe", "ipc_drops"]);
+    assert!(body.starts_with("projection_probe=42,ipc_drops="));
+    assert!(!body.contains("ticks="));
+    assert!(!body.contains("gauges="));
+}
+
+#[test]
+fn export_without_keys_returns_full_set() {
+    unlock();
+    observability::set_gauge("projection_full", 1);
+    let body = export_body(&[]);
+    assert!(body.starts_with("ticks="));
+    assert!(body.contains("quota_throttles="));
+    assert!(body.contains("projection_full=1"));
+
+    let plain = handle_export(OP_EXPORT_METRICS).expect("export");
+    assert!(String::from_utf8(plain[2..].to_vec()).unwrap().contains("projection_full=1"));
+}
+
+#[test]
+fn export_omits_unknown_keys() {
+    unlock();
+    observability::set_gauge("projection_mixed", 5);
+    let body = export_body(&["no_such_metric", "projection_mixed", "errors_total"]);
+    assert!(!body.contains("no_such_metric"));
+    assert!(body.starts_with("projection_mixed=5,errors_total="));
+}
@@ -0,0 +1,58 @@
+mod test_guard;
+use redmi_ia::handlers::ipc::{
+    handle_export, handle_export_version, ExportError, IpcSchemaVersion, OP_EXPORT_HEALTH,
+    OP_EXPORT_METRICS,
+};
+use redmi_ia::init::set_locked;
+use redmi_ia::security::tls::bundle::{store_bundle, TlsBundle};
+use redmi_ia::utils::observability;
+
+fn unlock() {
+    set_locked(false);
+    store_bundle(TlsBundle {
+        ticket: "t".into(),
+        routes: Vec::new(),
+        expires_at_ms: 1_000_000,
+        generation: 1,
+    });
+}
+
+#[test]
+fn export_each_supported_version() {
+    unlock();
+    observability::set_gauge("schema_probe", 7);
+
+    let v1 = handle_export_version(OP_EXPORT_METRICS, 1).expect("v1 export");
+    let (version, body) = IpcSchemaVersion::parse_header(&v1).expect("v1 header");
+    assert_eq!(version, IpcSchemaVersion::V1);
+    let body = core::str::from_utf8(body).unwrap();
+    assert!(body.starts_with("ticks="));
+    assert!(!body.contains("gauges="));
+
+    let v2 = handle_export_version(OP_EXPORT_METRICS, 2).expect("v2 export");
+    let (version, body) = IpcSchemaVersion::parse_header(&v2).expect("v2 header");
+    assert_eq!(version, IpcSchemaVersion::V2);
+    assert!(core::str::from_utf8(body).unwrap().contains("schema_probe=7"));
+
+    let health = handle_export_version(OP_EXPORT_HEALTH, 1).expect("health export");
+    assert_eq!(&health[..2], &IpcSchemaVersion::V1.header());
+}
+
+#[test]
+fn export_defaults_to_latest_version() {
+    unlock();
+    let payload = handle_export(OP_EXPORT_HEALTH).expect("export");
+    let (version, body) = IpcSchemaVersion::parse_header(&payload).expect("header");
+    assert_eq!(version, IpcSchemaVersion::LATEST);
+    assert!(core::str::from_utf8(body).unwrap().starts_with("status="));
+}
+
+#[test]
+fn export_rejects_unsupported_version() {
+    unlock();
+    assert_eq!(
+        handle_export_version(OP_EXPORT_METRICS, 99),
+        Err(ExportError::UnsupportedVersion(99))
+    );
+    assert_eq!(handle_export_version(1234, 2), Err(ExportError::UnknownOpcode));
+}
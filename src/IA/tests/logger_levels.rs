@@ -0,0 +1,58 @@
+mod test_guard;
+
+use redmi_ia::ia_log;
+use redmi_ia::utils::error::ErrorCode;
+use redmi_ia::utils::logger::{self, LogLevel, Logger};
+
+#[test]
+fn messages_below_threshold_are_suppressed() {
+	let mut log = Logger::new();
+	log.log(LogLevel::Debug, "loop", ErrorCode::ErrUnknown, "debug detail");
+	log.log(LogLevel::Trace, "loop", ErrorCode::ErrUnknown, "trace detail");
+	log.log(LogLevel::Warn, "loop", ErrorCode::ErrBusy, "slow tick");
+	let out = log.export_kv();
+	assert!(!out.contains("debug detail"));
+	assert!(!out.contains("trace detail"));
+	assert!(out.contains("log0.level=warn"));
+	assert!(!out.contains("log1."));
+}
+
+#[test]
+fn threshold_changes_take_effect_at_runtime() {
+	let mut log = Logger::new();
+	log.set_min_level(LogLevel::Trace);
+	log.log(LogLevel::Trace, "loop", ErrorCode::ErrUnknown, "first");
+	log.set_min_level(LogLevel::Error);
+	log.log(LogLevel::Warn, "loop", ErrorCode::ErrUnknown, "second");
+	log.log(LogLevel::Error, "loop", ErrorCode::ErrInternal, "third");
+	let out = log.export_kv();
+	assert!(out.contains("log0.level=trace") && out.contains("first"));
+	assert!(!out.contains("second"));
+	assert!(out.contains("log1.level=error") && out.contains("third"));
+}
+
+#[test]
+fn structured_fields_serialize_in_key_order() {
+	let mut log = Logger::new();
+	log.log_fields(
+		LogLevel::Info,
+		"ipc",
+		ErrorCode::ErrUnknown,
+		"routed",
+		&[("opcode", "9000"), ("channel", "3"), ("bytes", "42")],
+	);
+	assert!(log.export_kv().ends_with("log0.ctx=routed,log0.bytes=42,log0.channel=3,log0.opcode=9000;"));
+}
+
+#[test]
+fn macro_respects_global_threshold() {
+	logger::set_min_level(LogLevel::Warn);
+	ia_log!(LogLevel::Info, "macro", ErrorCode::ErrUnknown, "macro-info", "n" => 1);
+	ia_log!(LogLevel::Warn, "macro", ErrorCode::ErrUnknown, "macro-warn", "n" => 2, "tag" => "x");
+	let out = logger::export_logs();
+	assert!(!out.contains("macro-info"));
+	assert!(out.contains("ctx=macro-warn"));
+	assert!(out.contains(".n=2") && out.contains(".tag=x"));
+	logger::set_min_level(LogLevel::Info);
+	assert_eq!(logger::min_level(), LogLevel::Info);
+}
@@ -0,0 +1,47 @@
+mod test_guard;
+
+use redmi_ia::r#loop::{LoopManager, LOOP_NAMES};
+
+#[test]
+fn per_loop_counts_and_max_time_track_independently() {
+	let manager = LoopManager::new();
+	for tick in 0..5u64 {
+		assert!(manager.record_loop_run("primary", tick * 10, tick * 10 + tick));
+	}
+	manager.record_loop_run("secondary", 100, 103);
+	manager.record_loop_run("secondary", 200, 201);
+	manager.record_loop_run("external", 300, 312);
+
+	let primary = manager.profiling_for("primary").expect("primary profiling");
+	assert_eq!(primary.runs, 5);
+	assert_eq!(primary.total_run_ms, 10);
+	assert_eq!(primary.max_run_ms, 4);
+	assert_eq!(primary.last_run_ms, 40);
+
+	let secondary = manager.profiling_for("secondary").expect("secondary profiling");
+	assert_eq!(secondary.runs, 2);
+	assert_eq!(secondary.max_run_ms, 3);
+	assert_eq!(secondary.last_run_ms, 200);
+
+	let external = manager.profiling_for("external").expect("external profiling");
+	assert_eq!(external.runs, 1);
+	assert_eq!(external.max_run_ms, 12);
+
+	assert_eq!(manager.profiling_for("utility").map(|p| p.runs), Some(0));
+	assert!(manager.profiling_for("unknown").is_none());
+	assert!(!manager.record_loop_run("unknown", 0, 1));
+}
+
+#[test]
+fn reset_profiling_clears_every_loop() {
+	let manager = LoopManager::new();
+	for name in LOOP_NAMES.iter() {
+		manager.record_loop_run(name, 0, 7);
+	}
+	manager.reset_profiling();
+	for name in LOOP_NAMES.iter() {
+		let profiling = manager.profiling_for(name).expect("profiling");
+		assert_eq!(profiling.runs, 0);
+		assert_eq!(profiling.max_run_ms, 0);
+	}
+}
@@ -0,0 +1,34 @@
+#![cfg(not(feature = "ml_full"))]
+mod test_guard;
+
+use redmi_ia::ml::{FaceModel, FingerprintModel, VoiceModel};
+
+const THRESHOLD: f32 = 0.75;
+const ABOVE: [f32; 4] = [0.8, 0.7, 0.9, 0.62];
+const BELOW: [f32; 4] = [0.8, 0.7, 0.9, 0.58];
+
+#[test]
+fn face_model_decides_around_threshold() {
+	let model = FaceModel::new();
+	assert!((model.score(&ABOVE) - 0.755).abs() < 1e-5);
+	assert!(model.decide(&ABOVE, THRESHOLD));
+	assert!(!model.decide(&BELOW, THRESHOLD));
+	assert_eq!(model.score(&ABOVE), model.score(&ABOVE));
+}
+
+#[test]
+fn voice_model_decides_around_threshold() {
+	let model = VoiceModel::new();
+	assert!((model.score(&BELOW) - 0.745).abs() < 1e-5);
+	assert!(model.decide(&ABOVE, THRESHOLD));
+	assert!(!model.decide(&BELOW, THRESHOLD));
+	assert_eq!(model.score(&[]), 0.0);
+}
+
+#[test]
+fn fingerprint_model_decides_around_threshold() {
+	let model = FingerprintModel::new();
+	assert!(model.decide(&ABOVE, THRESHOLD));
+	assert!(!model.decide(&BELOW, THRESHOLD));
+	assert_eq!(model.score(&[1.5, f32::NAN, -0.5]), 0.5);
+}
@@ -0,0 +1,64 @@
+#![cfg(not(feature = "ml_full"))]
+mod test_guard;
+
+use redmi_ia::ml::preprocess::{min_max_scale, normalize_l2, zscore};
+use redmi_ia::ml::FaceModel;
+
+fn close(a: f32, b: f32) -> bool {
+	(a - b).abs() < 1e-5
+}
+
+#[test]
+fn normalize_l2_scales_to_unit_norm() {
+	let mut values = [3.0f32, 4.0];
+	normalize_l2(&mut values);
+	assert!(close(values[0], 0.6) && close(values[1], 0.8));
+
+	let mut equal = [2.0f32; 4];
+	normalize_l2(&mut equal);
+	assert!(equal.iter().all(|v| close(*v, 0.5)));
+
+	let mut zeros = [0.0f32; 3];
+	normalize_l2(&mut zeros);
+	assert!(zeros.iter().all(|v| *v == 0.0));
+
+	let mut empty: [f32; 0] = [];
+	normalize_l2(&mut empty);
+}
+
+#[test]
+fn min_max_scale_maps_range() {
+	let mut values = [2.0f32, 4.0, 6.0];
+	min_max_scale(&mut values, 0.0, 1.0);
+	assert!(close(values[0], 0.0) && close(values[1], 0.5) && close(values[2], 1.0));
+
+	let mut equal = [7.0f32; 3];
+	min_max_scale(&mut equal, -1.0, 1.0);
+	assert!(equal.iter().all(|v| !v.is_nan() && *v == -1.0));
+
+	let mut empty: [f32; 0] = [];
+	min_max_scale(&mut empty, 0.0, 1.0);
+}
+
+#[test]
+fn zscore_standardises_values() {
+	let mut values = [1.0f32, 2.0, 3.0];
+	zscore(&mut values);
+	assert!(close(values.iter().sum::<f32>(), 0.0));
+	assert!(close(values[2], 1.224_745));
+
+	let mut equal = [5.0f32; 4];
+	zscore(&mut equal);
+	assert!(equal.iter().all(|v| !v.is_nan() && *v == 0.0));
+
+	let mut empty: [f32; 0] = [];
+	zscore(&mut empty);
+}
+
+#[test]
+fn models_share_normalised_similarity() {
+	let model = FaceModel::new();
+	assert!(close(model.similarity(&[10, 20, 30], &[20, 40, 60]), 1.0));
+	assert_eq!(model.similarity(&[0, 0], &[1, 2]), 0.0);
+	assert_eq!(model.similarity(&[], &[1]), 0.0);
+}
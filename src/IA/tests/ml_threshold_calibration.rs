@@ -0,0 +1,33 @@
+#![cfg(not(feature = "ml_full"))]
+mod test_guard;
+
+use redmi_ia::ml::calibrate_threshold;
+
+fn mean(values: &[f32]) -> f32 {
+	values.iter().sum::<f32>() / values.len() as f32
+}
+
+#[test]
+fn separated_distributions_calibrate_with_zero_eer() {
+	let genuine = [0.8f32, 0.85, 0.9, 0.95];
+	let impostor = [0.1f32, 0.2, 0.3, 0.25];
+	let calibration = calibrate_threshold(&genuine, &impostor).expect("calibration");
+	assert_eq!(calibration.eer, 0.0);
+	assert!((calibration.threshold - 0.55).abs() < 1e-5);
+	assert!(calibration.threshold > mean(&impostor) && calibration.threshold < mean(&genuine));
+}
+
+#[test]
+fn overlapping_distributions_report_higher_eer() {
+	let genuine = [0.5f32, 0.6, 0.7, 0.8];
+	let impostor = [0.3f32, 0.4, 0.55, 0.65];
+	let calibration = calibrate_threshold(&genuine, &impostor).expect("calibration");
+	assert!((calibration.eer - 0.25).abs() < 1e-5);
+	assert!(calibration.threshold > mean(&impostor) && calibration.threshold < mean(&genuine));
+}
+
+#[test]
+fn empty_score_sets_cannot_be_calibrated() {
+	assert!(calibrate_threshold(&[], &[0.1]).is_none());
+	assert!(calibrate_threshold(&[0.9], &[]).is_none());
+}
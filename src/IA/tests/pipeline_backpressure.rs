@@ -0,0 +1,87 @@
+mod test_guard;
+
+use redmi_ia::r#loop::{BackpressurePolicy, PipelineExecutor, StageTimeoutPolicy};
+
+fn fill_stage_one(executor: &PipelineExecutor, count: u32) -> Vec<u32> {
+	let mut ids = Vec::new();
+	for ctx in 0..count {
+		let id = executor.create_pipeline_at(ctx.into(), 0);
+		ids.push(id);
+	}
+	ids
+}
+
+#[test]
+fn full_downstream_queue_blocks_producer() {
+	let executor = PipelineExecutor::new();
+	executor.set_stage_capacity(1, Some(2));
+	executor.set_backpressure_policy(BackpressurePolicy::Block);
+	let ids = fill_stage_one(&executor, 3);
+
+	assert_eq!(executor.progress_task_at(ids[0], 0, 1), Some(1));
+	assert_eq!(executor.progress_task_at(ids[1], 0, 1), Some(1));
+	assert_eq!(executor.progress_task_at(ids[2], 0, 1), None);
+	assert_eq!(executor.stage_queue_depth(0), 1);
+	assert_eq!(executor.stage_queue_depth(1), 2);
+	assert_eq!(executor.get_metrics().dropped_tasks, 0);
+
+	assert_eq!(executor.progress_task_at(ids[0], 1, 2), Some(2));
+	assert_eq!(executor.progress_task_at(ids[2], 0, 2), Some(1));
+	let metrics = executor.get_metrics();
+	assert!(metrics.stage_queue_depths.contains(&(0, 0)));
+	assert!(metrics.stage_queue_depths.contains(&(1, 2)));
+}
+
+#[test]
+fn full_downstream_queue_sheds_with_counting() {
+	let executor = PipelineExecutor::new();
+	executor.set_stage_capacity(1, Some(1));
+	executor.set_backpressure_policy(BackpressurePolicy::Shed);
+	let ids = fill_stage_one(&executor, 3);
+
+	assert_eq!(executor.progress_task_at(ids[0], 0, 1), Some(1));
+	assert_eq!(executor.progress_task_at(ids[1], 0, 1), None);
+	assert_eq!(executor.progress_task_at(ids[2], 0, 1), None);
+
+	let metrics = executor.get_metrics();
+	assert_eq!(metrics.dropped_tasks, 2);
+	assert_eq!(executor.stage_queue_depth(0), 0);
+	assert_eq!(executor.stage_queue_depth(1), 1);
+}
+
+#[test]
+fn skipped_timeout_into_full_stage_stays_pending_under_block() {
+	let executor = PipelineExecutor::new();
+	executor.set_stage_timeout(0, Some(50));
+	executor.set_timeout_policy(StageTimeoutPolicy::Skip);
+	executor.set_stage_capacity(1, Some(1));
+	executor.set_backpressure_policy(BackpressurePolicy::Block);
+	let ids = fill_stage_one(&executor, 2);
+
+	assert_eq!(executor.progress_task_at(ids[0], 0, 1), Some(1));
+	assert_eq!(executor.abort_timed_out(80), 0);
+	assert_eq!(executor.stage_queue_depth(0), 1);
+	assert_eq!(executor.stage_queue_depth(1), 1);
+	assert_eq!(executor.get_metrics().dropped_tasks, 0);
+
+	assert_eq!(executor.progress_task_at(ids[0], 1, 90), Some(2));
+	assert_eq!(executor.abort_timed_out(100), 1);
+	assert_eq!(executor.stage_queue_depth(0), 0);
+	assert_eq!(executor.get_pending_at_stage(1)[0].task_id, ids[1]);
+}
+
+#[test]
+fn skipped_timeout_into_full_stage_is_shed_under_shed() {
+	let executor = PipelineExecutor::new();
+	executor.set_stage_timeout(0, Some(50));
+	executor.set_timeout_policy(StageTimeoutPolicy::Skip);
+	executor.set_stage_capacity(1, Some(1));
+	executor.set_backpressure_policy(BackpressurePolicy::Shed);
+	let ids = fill_stage_one(&executor, 2);
+
+	assert_eq!(executor.progress_task_at(ids[0], 0, 1), Some(1));
+	assert_eq!(executor.abort_timed_out(80), 1);
+	assert_eq!(executor.stage_queue_depth(0), 0);
+	assert_eq!(executor.stage_queue_depth(1), 1);
+	assert_eq!(executor.get_metrics().dropped_tasks, 1);
+}
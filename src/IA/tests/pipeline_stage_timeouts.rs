@@ -0,0 +1,51 @@
+mod test_guard;
+
+use redmi_ia::r#loop::{PipelineExecutor, StageTimeoutPolicy};
+
+#[test]
+fn stage_within_budget_completes() {
+	let executor = PipelineExecutor::new();
+	executor.set_stage_timeout(0, Some(50));
+	let task_id = executor.create_pipeline_at(1, 0);
+
+	assert_eq!(executor.abort_timed_out(30), 0);
+	assert_eq!(executor.progress_task_at(task_id, 0, 30), Some(1));
+	assert_eq!(executor.get_metrics().stage_timeouts, 0);
+	assert_eq!(executor.get_pending_at_stage(1).len(), 1);
+}
+
+#[test]
+fn slow_stage_fails_task_under_fail_policy() {
+	let executor = PipelineExecutor::new();
+	executor.set_stage_timeout(0, Some(50));
+	executor.set_timeout_policy(StageTimeoutPolicy::Fail);
+	let task_id = executor.create_pipeline_at(1, 0);
+
+	assert_eq!(executor.abort_timed_out(80), 1);
+	let metrics = executor.get_metrics();
+	assert_eq!(metrics.stage_timeouts, 1);
+	assert_eq!(metrics.total_failed, 1);
+	assert!(executor.get_pending_at_stage(0).is_empty());
+	assert!(executor.get_pending_at_stage(1).is_empty());
+	assert_eq!(executor.progress_task_at(task_id, 1, 90), None);
+}
+
+#[test]
+fn slow_stage_is_skipped_under_skip_policy() {
+	let executor = PipelineExecutor::new();
+	executor.set_stage_timeout(0, Some(50));
+	executor.set_timeout_policy(StageTimeoutPolicy::Skip);
+	let task_id = executor.create_pipeline_at(1, 0);
+
+	assert_eq!(executor.abort_timed_out(80), 1);
+	let metrics = executor.get_metrics();
+	assert_eq!(metrics.stage_timeouts, 1);
+	assert_eq!(metrics.total_failed, 0);
+
+	let next = executor.get_pending_at_stage(1);
+	assert_eq!(next.len(), 1);
+	assert_eq!(next[0].task_id, task_id);
+	assert_eq!(next[0].started_at_ms, 80);
+	assert_eq!(executor.abort_timed_out(500), 0);
+	assert_eq!(executor.progress_task_at(task_id, 1, 500), Some(2));
+}
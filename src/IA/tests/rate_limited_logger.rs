@@ -0,0 +1,58 @@
+mod test_guard;
+
+use redmi_ia::utils::error::ErrorCode;
+use redmi_ia::utils::logger::LogLevel;
+use redmi_ia::utils::observability::{RateDecision, RateLimitedLogger, MAX_RATE_KEYS};
+
+#[test]
+fn first_messages_pass_then_repeats_are_suppressed() {
+	let mut limiter = RateLimitedLogger::new(3, 1000);
+	for now in 0..3u64 {
+		assert_eq!(
+			limiter.log_at(LogLevel::Error, "loop", ErrorCode::ErrTimeout, "tick overrun", now),
+			Some("tick overrun".into())
+		);
+	}
+	for now in 3..10u64 {
+		assert_eq!(limiter.log_at(LogLevel::Error, "loop", ErrorCode::ErrTimeout, "tick overrun", now), None);
+	}
+	assert_eq!(limiter.check("other message", 10), RateDecision::Emit { suppressed: 0 });
+}
+
+#[test]
+fn suppression_count_is_reported_after_window_rolls() {
+	let mut limiter = RateLimitedLogger::new(1, 100);
+	assert!(limiter.log_at(LogLevel::Warn, "ipc", ErrorCode::ErrBusy, "queue full", 0).is_some());
+	assert!(limiter.log_at(LogLevel::Warn, "ipc", ErrorCode::ErrBusy, "queue full", 40).is_none());
+	assert!(limiter.log_at(LogLevel::Warn, "ipc", ErrorCode::ErrBusy, "queue full", 99).is_none());
+
+	assert_eq!(
+		limiter.log_at(LogLevel::Warn, "ipc", ErrorCode::ErrBusy, "queue full", 100),
+		Some("queue full (suppressed 2 times)".into())
+	);
+	assert!(limiter.log_at(LogLevel::Warn, "ipc", ErrorCode::ErrBusy, "queue full", 150).is_none());
+	assert_eq!(limiter.check("queue full", 250), RateDecision::Emit { suppressed: 1 });
+}
+
+#[test]
+fn messages_below_min_level_are_not_logged() {
+	let mut limiter = RateLimitedLogger::new(1, 1000);
+	assert_eq!(limiter.log_at(LogLevel::Trace, "loop", ErrorCode::ErrTimeout, "noisy", 0), None);
+	assert_eq!(limiter.tracked_keys(), 0);
+	assert_eq!(
+		limiter.log_at(LogLevel::Error, "loop", ErrorCode::ErrTimeout, "noisy", 1),
+		Some("noisy".into())
+	);
+}
+
+#[test]
+fn window_table_stays_bounded() {
+	let mut limiter = RateLimitedLogger::new(1, 100);
+	for i in 0..(MAX_RATE_KEYS as u64 * 2) {
+		limiter.check(&format!("burst {}", i), i);
+	}
+	assert!(limiter.tracked_keys() <= MAX_RATE_KEYS);
+
+	limiter.check("late", 10_000);
+	assert_eq!(limiter.tracked_keys(), 1);
+}
@@ -0,0 +1,48 @@
+mod test_guard;
+
+use redmi_ia::io::decode_with_len;
+use redmi_ia::utils::trace_buffer::TraceBuffer;
+
+fn drain_frames(buffer: &mut TraceBuffer) -> Vec<Vec<u8>> {
+	let mut frames = Vec::new();
+	buffer.drain_to(&mut |frame: &[u8]| frames.push(frame.to_vec()));
+	frames
+}
+
+#[test]
+fn drain_emits_length_prefixed_records_in_order() {
+	let mut buffer = TraceBuffer::new(4);
+	buffer.push("boot".into());
+	buffer.push("loop=primary".into());
+	buffer.push("".into());
+
+	let frames = drain_frames(&mut buffer);
+	assert_eq!(frames.len(), 3);
+	let mut decoded = Vec::new();
+	for frame in frames.iter() {
+		let (payload, used) = decode_with_len(frame).expect("framed record");
+		assert_eq!(used, frame.len());
+		decoded.push(payload);
+	}
+	assert_eq!(decoded, vec![b"boot".to_vec(), b"loop=primary".to_vec(), Vec::new()]);
+	assert_eq!(buffer.len(), 0);
+	assert!(drain_frames(&mut buffer).is_empty());
+	assert_eq!(buffer.dropped(), 0);
+}
+
+#[test]
+fn overflow_counts_dropped_records_and_keeps_survivors() {
+	let mut buffer = TraceBuffer::new(2);
+	for entry in ["a", "b", "c", "d", "e"] {
+		buffer.push(entry.into());
+	}
+	assert_eq!(buffer.dropped(), 3);
+
+	let frames = drain_frames(&mut buffer);
+	let survivors: Vec<Vec<u8>> = frames
+		.iter()
+		.map(|frame| decode_with_len(frame).expect("framed record").0)
+		.collect();
+	assert_eq!(survivors, vec![b"d".to_vec(), b"e".to_vec()]);
+	assert_eq!(buffer.dropped(), 3);
+}
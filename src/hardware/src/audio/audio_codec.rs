@@ -1,4 +1,6 @@
 use core::ptr::{read_volatile, write_volatile};
+use super::speaker::SPEAKER_VOLUME_OFFSET;
+use crate::device_interfaces::mmio::{MmioRegisters, RegisterBank};
 
 const CODEC_CTRL_OFFSET: u64 = 0x0000;
 const CODEC_STATUS_OFFSET: u64 = 0x0004;
@@ -108,3 +110,112 @@ pub fn set_mode(mode: u32) -> Result<(), &'static str> {
     }
     Ok(())
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioError {
+    UnsupportedRate,
+    UnsupportedFormat,
+    UnsupportedChannels,
+}
+
+const SUPPORTED_RATES: [u32; 4] = [8_000, 16_000, 44_100, 48_000];
+const SPEAKER_VOLUME_MAX: u32 = 0xFF;
+
+/// Packs a validated stream format into the codec config register:
+/// bits 0-1 rate index, bits 4-5 sample width, bit 8 stereo.
+pub fn encode_format(sample_rate: u32, bits: u8, channels: u8) -> Result<u32, AudioError> {
+    let rate = SUPPORTED_RATES
+        .iter()
+        .position(|r| *r == sample_rate)
+        .ok_or(AudioError::UnsupportedRate)? as u32;
+    let width = match bits {
+        16 => 0,
+        24 => 1,
+        32 => 2,
+        _ => return Err(AudioError::UnsupportedFormat),
+    };
+    let stereo = match channels {
+        1 => 0,
+        2 => 1,
+        _ => return Err(AudioError::UnsupportedChannels),
+    };
+    Ok(rate | (width << 4) | (stereo << 8))
+}
+
+pub struct AudioCodec<R: RegisterBank = MmioRegisters> {
+    codec: R,
+    speaker: R,
+}
+
+impl AudioCodec {
+    pub fn new() -> Self {
+        AudioCodec::with_registers(
+            MmioRegisters::new(crate::audio_codec_base()),
+            MmioRegisters::new(crate::speaker_base()),
+        )
+    }
+}
+
+impl Default for AudioCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: RegisterBank> AudioCodec<R> {
+    pub fn with_registers(codec: R, speaker: R) -> Self {
+        AudioCodec { codec, speaker }
+    }
+
+    /// Programs the stream format and enables the codec. Nothing is
+    /// written when the combination is unsupported.
+    pub fn configure(&mut self, sample_rate: u32, bits: u8, channels: u8) -> Result<(), AudioError> {
+        let format = encode_format(sample_rate, bits, channels)?;
+        self.codec.write32(CODEC_CONFIG_OFFSET, format);
+        self.codec.write32(CODEC_CTRL_OFFSET, 0x1);
+        Ok(())
+    }
+
+    /// Sets speaker-path volume; `percent` is clamped to 100.
+    pub fn set_volume(&mut self, percent: u8) {
+        let value = percent.min(100) as u32 * SPEAKER_VOLUME_MAX / 100;
+        self.speaker.write32(SPEAKER_VOLUME_OFFSET, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device_interfaces::mmio::test_support::MemoryBank;
+
+    fn codec() -> AudioCodec<MemoryBank> {
+        AudioCodec::with_registers(MemoryBank::default(), MemoryBank::default())
+    }
+
+    #[test]
+    fn test_supported_configurations_write_format() {
+        let mut codec = codec();
+        codec.configure(48_000, 16, 2).unwrap();
+        assert_eq!(codec.codec.get(CODEC_CONFIG_OFFSET), 0x103);
+        assert_eq!(codec.codec.get(CODEC_CTRL_OFFSET), 0x1);
+
+        codec.configure(8_000, 24, 1).unwrap();
+        assert_eq!(codec.codec.get(CODEC_CONFIG_OFFSET), 0x010);
+        codec.configure(44_100, 32, 2).unwrap();
+        assert_eq!(codec.codec.get(CODEC_CONFIG_OFFSET), 0x122);
+
+        codec.set_volume(50);
+        assert_eq!(codec.speaker.get(SPEAKER_VOLUME_OFFSET), 127);
+        codec.set_volume(200);
+        assert_eq!(codec.speaker.get(SPEAKER_VOLUME_OFFSET), SPEAKER_VOLUME_MAX);
+    }
+
+    #[test]
+    fn test_unsupported_rate_is_rejected() {
+        let mut codec = codec();
+        assert_eq!(codec.configure(22_050, 16, 2), Err(AudioError::UnsupportedRate));
+        assert_eq!(codec.configure(48_000, 8, 2), Err(AudioError::UnsupportedFormat));
+        assert_eq!(codec.configure(48_000, 16, 6), Err(AudioError::UnsupportedChannels));
+        assert!(codec.codec.regs.borrow().is_empty());
+    }
+}
@@ -5,7 +5,7 @@ const MIC_STATUS_OFFSET: u64 = 0x0004;
 const MIC_DATA_OFFSET: u64 = 0x0008;
 const MIC_GAIN_OFFSET: u64 = 0x000C;
 const MIC_CONFIG_OFFSET: u64 = 0x0010;
-const MIC_MODE_OFFSET: u64 = 0x0014;
+pub(crate) const MIC_MODE_OFFSET: u64 = 0x0014;
 const MIC_BUFFER_OFFSET: u64 = 0x0018;
 const MIC_COUNT_OFFSET: u64 = 0x001C;
 
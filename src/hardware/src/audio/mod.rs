@@ -4,6 +4,7 @@ pub mod headphone_jack;
 pub mod microphone;
 pub mod noise_cancellation;
 pub mod speaker;
-pub use audio_codec::AudioCodec;
+pub use audio_codec::{AudioCodec, AudioError};
 pub use microphone::Microphone;
+pub use noise_cancellation::{AncController, AncMode};
 pub use speaker::Speaker;
@@ -1,4 +1,7 @@
 use core::ptr::{read_volatile, write_volatile};
+use super::microphone::MIC_MODE_OFFSET;
+use super::speaker::SPEAKER_MODE_OFFSET;
+use crate::device_interfaces::mmio::{MmioRegisters, RegisterBank};
 
 const ANC_CTRL_OFFSET: u64 = 0x0000;
 const ANC_STATUS_OFFSET: u64 = 0x0004;
@@ -96,3 +99,129 @@ pub fn set_gain(gain: u32) -> Result<(), &'static str> {
     }
     Ok(())
 }
+
+const ANC_CTRL_ENABLE: u32 = 0x1;
+const ANC_LEVEL_MAX: u32 = 0x3FF;
+const ANC_MODE_ACTIVE: u32 = 0x1;
+/// Mode bit routing the mic feed into, and the anti-noise signal out of,
+/// the ANC block.
+const PATH_MODE_ANC: u32 = 0x10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AncMode {
+    Off,
+    Active,
+}
+
+/// Per-session ANC control. Enabling also switches the microphone and
+/// speaker paths into ANC routing; disabling restores them.
+pub struct AncController<R: RegisterBank = MmioRegisters> {
+    anc: R,
+    mic: R,
+    speaker: R,
+}
+
+impl AncController {
+    pub fn new() -> Self {
+        AncController::with_registers(
+            MmioRegisters::new(crate::noise_cancellation_base()),
+            MmioRegisters::new(crate::microphone_base()),
+            MmioRegisters::new(crate::speaker_base()),
+        )
+    }
+}
+
+impl Default for AncController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: RegisterBank> AncController<R> {
+    pub fn with_registers(anc: R, mic: R, speaker: R) -> Self {
+        AncController { anc, mic, speaker }
+    }
+
+    fn update(regs: &R, offset: u64, bits: u32, set: bool) {
+        let value = regs.read32(offset);
+        let value = if set { value | bits } else { value & !bits };
+        regs.write32(offset, value);
+    }
+
+    fn route(&mut self, enabled: bool) {
+        Self::update(&self.mic, MIC_MODE_OFFSET, PATH_MODE_ANC, enabled);
+        Self::update(&self.speaker, SPEAKER_MODE_OFFSET, PATH_MODE_ANC, enabled);
+        Self::update(&self.anc, ANC_CTRL_OFFSET, ANC_CTRL_ENABLE, enabled);
+        self.anc.write32(ANC_MODE_OFFSET, if enabled { ANC_MODE_ACTIVE } else { 0 });
+    }
+
+    pub fn enable(&mut self) {
+        self.route(true);
+    }
+
+    pub fn disable(&mut self) {
+        self.route(false);
+    }
+
+    pub fn mode(&self) -> AncMode {
+        if self.anc.read32(ANC_MODE_OFFSET) & ANC_MODE_ACTIVE != 0 {
+            AncMode::Active
+        } else {
+            AncMode::Off
+        }
+    }
+
+    /// Sets suppression strength; `level` is clamped to 0-100 and scaled
+    /// linearly onto the level register's range. Returns the applied level.
+    pub fn set_intensity(&mut self, level: u8) -> u8 {
+        let level = level.min(100);
+        let value = level as u32 * ANC_LEVEL_MAX / 100;
+        self.anc.write32(ANC_LEVEL_OFFSET, value);
+        level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device_interfaces::mmio::test_support::MemoryBank;
+
+    fn controller() -> AncController<MemoryBank> {
+        AncController::with_registers(MemoryBank::default(), MemoryBank::default(), MemoryBank::default())
+    }
+
+    #[test]
+    fn test_enable_disable_toggle_control_and_paths() {
+        let mut anc = controller();
+        anc.mic.set(MIC_MODE_OFFSET, 0x3);
+        assert_eq!(anc.mode(), AncMode::Off);
+        anc.enable();
+        assert_eq!(anc.mode(), AncMode::Active);
+        assert_eq!(anc.anc.get(ANC_CTRL_OFFSET) & ANC_CTRL_ENABLE, ANC_CTRL_ENABLE);
+        assert_eq!(anc.anc.get(ANC_MODE_OFFSET), ANC_MODE_ACTIVE);
+        assert_eq!(anc.mic.get(MIC_MODE_OFFSET), 0x3 | PATH_MODE_ANC);
+        assert_eq!(anc.speaker.get(SPEAKER_MODE_OFFSET), PATH_MODE_ANC);
+
+        anc.disable();
+        assert_eq!(anc.mode(), AncMode::Off);
+        assert_eq!(anc.anc.get(ANC_CTRL_OFFSET), 0);
+        assert_eq!(anc.anc.get(ANC_MODE_OFFSET), 0);
+        assert_eq!(anc.mic.get(MIC_MODE_OFFSET), 0x3);
+        assert_eq!(anc.speaker.get(SPEAKER_MODE_OFFSET), 0);
+    }
+
+    #[test]
+    fn test_intensity_is_clamped_and_linear() {
+        let mut anc = controller();
+        assert_eq!(anc.set_intensity(0), 0);
+        assert_eq!(anc.anc.get(ANC_LEVEL_OFFSET), 0);
+        anc.set_intensity(25);
+        assert_eq!(anc.anc.get(ANC_LEVEL_OFFSET), 255);
+        anc.set_intensity(50);
+        assert_eq!(anc.anc.get(ANC_LEVEL_OFFSET), 511);
+        assert_eq!(anc.set_intensity(100), 100);
+        assert_eq!(anc.anc.get(ANC_LEVEL_OFFSET), ANC_LEVEL_MAX);
+        assert_eq!(anc.set_intensity(250), 100);
+        assert_eq!(anc.anc.get(ANC_LEVEL_OFFSET), ANC_LEVEL_MAX);
+    }
+}
@@ -2,12 +2,12 @@ use core::ptr::{read_volatile, write_volatile};
 
 const SPEAKER_CTRL_OFFSET: u64 = 0x0000;
 const SPEAKER_STATUS_OFFSET: u64 = 0x0004;
-const SPEAKER_VOLUME_OFFSET: u64 = 0x0008;
+pub(crate) const SPEAKER_VOLUME_OFFSET: u64 = 0x0008;
 const SPEAKER_PLAY_OFFSET: u64 = 0x000C;
 const SPEAKER_STOP_OFFSET: u64 = 0x0010;
 const SPEAKER_DATA_OFFSET: u64 = 0x0014;
 const SPEAKER_CONFIG_OFFSET: u64 = 0x0018;
-const SPEAKER_MODE_OFFSET: u64 = 0x001C;
+pub(crate) const SPEAKER_MODE_OFFSET: u64 = 0x001C;
 
 fn speaker_reg(offset: u64) -> u64 {
     crate::speaker_base() + offset
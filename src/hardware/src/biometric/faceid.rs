@@ -1,5 +1,7 @@
 use core::ptr::{read_volatile, write_volatile};
 
+use super::{MmioRegisters, RegisterBank};
+
 const FACEID_CTRL_OFFSET: u64 = 0x0000;
 const FACEID_STATUS_OFFSET: u64 = 0x0004;
 const FACEID_ENROLL_OFFSET: u64 = 0x0008;
@@ -8,6 +10,8 @@ const FACEID_CONF_OFFSET: u64 = 0x0010;
 const FACEID_ATTEMPTS_OFFSET: u64 = 0x0014;
 const FACEID_LOCK_OFFSET: u64 = 0x0018;
 const FACEID_DATA_OFFSET: u64 = 0x001C;
+pub const FACEID_DEFAULT_THRESHOLD: u32 = 80;
+pub const FACEID_DEFAULT_MAX_ATTEMPTS: u32 = 5;
 
 fn faceid_reg(offset: u64) -> u64 {
     crate::faceid_base() + offset
@@ -98,4 +102,123 @@ pub fn enroll(data: u32) -> Result<(), &'static str> {
 
 pub fn verify(data: u32) -> Result<u32, &'static str> {
     verify_face(data)
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchResult {
+    Accepted { score: u32 },
+    Rejected { score: u32, attempts: u32 },
+    LockedOut,
+}
+
+pub struct FaceID<R: RegisterBank = MmioRegisters> {
+    regs: R,
+    threshold: u32,
+    max_attempts: u32,
+}
+
+impl FaceID {
+    pub fn new() -> Self {
+        FaceID::with_registers(MmioRegisters::new(crate::faceid_base()))
+    }
+}
+
+impl<R: RegisterBank> FaceID<R> {
+    pub fn with_registers(regs: R) -> Self {
+        FaceID {
+            regs,
+            threshold: FACEID_DEFAULT_THRESHOLD,
+            max_attempts: FACEID_DEFAULT_MAX_ATTEMPTS,
+        }
+    }
+
+    pub fn set_threshold(&mut self, threshold: u32) {
+        self.threshold = threshold;
+    }
+
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    pub fn set_max_attempts(&mut self, max_attempts: u32) -> Result<(), &'static str> {
+        if max_attempts == 0 {
+            return Err("max_attempts must be non-zero");
+        }
+        self.max_attempts = max_attempts;
+        Ok(())
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.regs.read32(FACEID_ATTEMPTS_OFFSET)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.regs.read32(FACEID_LOCK_OFFSET) != 0
+    }
+
+    /// Scores `probe` against the enrolled face. Consecutive rejects are
+    /// counted in the attempts register; reaching `max_attempts` sets the
+    /// lock register, and a successful match clears the count.
+    pub fn verify(&mut self, probe: u32) -> MatchResult {
+        if self.is_locked() {
+            return MatchResult::LockedOut;
+        }
+        self.regs.write32(FACEID_VERIFY_OFFSET, probe);
+        let score = self.regs.read32(FACEID_VERIFY_OFFSET);
+        if score >= self.threshold {
+            self.regs.write32(FACEID_ATTEMPTS_OFFSET, 0);
+            return MatchResult::Accepted { score };
+        }
+        let attempts = self.attempts().saturating_add(1);
+        self.regs.write32(FACEID_ATTEMPTS_OFFSET, attempts);
+        if attempts >= self.max_attempts {
+            self.regs.write32(FACEID_LOCK_OFFSET, 1);
+            return MatchResult::LockedOut;
+        }
+        MatchResult::Rejected { score, attempts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biometric::test_support::ScoredBank;
+
+    fn verify_with_score(face: &mut FaceID<ScoredBank>, score: u32) -> MatchResult {
+        face.regs.score = score;
+        face.verify(0xFACE)
+    }
+
+    #[test]
+    fn test_sub_threshold_counts_as_failure() {
+        let mut face = FaceID::with_registers(ScoredBank::new(FACEID_VERIFY_OFFSET, 0));
+        face.set_threshold(80);
+        assert_eq!(verify_with_score(&mut face, 79), MatchResult::Rejected { score: 79, attempts: 1 });
+        assert_eq!(verify_with_score(&mut face, 80), MatchResult::Accepted { score: 80 });
+    }
+
+    #[test]
+    fn test_lockout_after_max_attempts() {
+        let mut face = FaceID::with_registers(ScoredBank::new(FACEID_VERIFY_OFFSET, 0));
+        face.set_max_attempts(3).unwrap();
+        assert!(matches!(verify_with_score(&mut face, 10), MatchResult::Rejected { attempts: 1, .. }));
+        assert!(matches!(verify_with_score(&mut face, 10), MatchResult::Rejected { attempts: 2, .. }));
+        assert!(!face.is_locked());
+        assert_eq!(verify_with_score(&mut face, 10), MatchResult::LockedOut);
+        assert!(face.is_locked());
+        assert_eq!(verify_with_score(&mut face, 99), MatchResult::LockedOut);
+    }
+
+    #[test]
+    fn test_success_resets_counter() {
+        let mut face = FaceID::with_registers(ScoredBank::new(FACEID_VERIFY_OFFSET, 0));
+        face.set_max_attempts(3).unwrap();
+        verify_with_score(&mut face, 10);
+        verify_with_score(&mut face, 10);
+        assert_eq!(face.attempts(), 2);
+        assert_eq!(verify_with_score(&mut face, 95), MatchResult::Accepted { score: 95 });
+        assert_eq!(face.attempts(), 0);
+        assert!(matches!(verify_with_score(&mut face, 10), MatchResult::Rejected { attempts: 1, .. }));
+        assert!(!face.is_locked());
+    }
+}
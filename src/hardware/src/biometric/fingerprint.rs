@@ -1,5 +1,7 @@
 use core::ptr::{read_volatile, write_volatile};
 
+use super::{MmioRegisters, RegisterBank};
+
 const FP_CTRL_OFFSET: u64 = 0x0000;
 const FP_STATUS_OFFSET: u64 = 0x0004;
 const FP_ENROLL_OFFSET: u64 = 0x0008;
@@ -8,6 +10,9 @@ const FP_TEMPLATE_OFFSET: u64 = 0x0010;
 const FP_ATTEMPTS_OFFSET: u64 = 0x0014;
 const FP_LOCK_OFFSET: u64 = 0x0018;
 const FP_DATA_OFFSET: u64 = 0x001C;
+const FP_TEMPLATE_REGION_OFFSET: u64 = 0x0100;
+const FP_TEMPLATE_SLOT_WORDS: u64 = 16;
+pub const FP_MAX_TEMPLATE_SLOTS: u32 = 8;
 
 fn fp_reg(offset: u64) -> u64 {
     crate::fingerprint_base() + offset
@@ -85,3 +90,121 @@ pub fn write_data(data: u32) -> Result<(), &'static str> {
     }
     Ok(())
 }
+
+pub struct Fingerprint<R: RegisterBank = MmioRegisters> {
+    regs: R,
+}
+
+impl Fingerprint {
+    pub fn new() -> Self {
+        Fingerprint { regs: MmioRegisters::new(crate::fingerprint_base()) }
+    }
+}
+
+impl<R: RegisterBank> Fingerprint<R> {
+    pub fn with_registers(regs: R) -> Self {
+        Fingerprint { regs }
+    }
+
+    pub fn template_count(&self) -> u32 {
+        self.regs.read32(FP_TEMPLATE_OFFSET)
+    }
+
+    fn slot_offset(slot: u32, word: u64) -> u64 {
+        FP_TEMPLATE_REGION_OFFSET + (slot as u64 * FP_TEMPLATE_SLOT_WORDS + word) * 4
+    }
+
+    fn slot_is_clear(&self, slot: u32) -> bool {
+        (0..FP_TEMPLATE_SLOT_WORDS).all(|word| self.regs.read32(Self::slot_offset(slot, word)) == 0)
+    }
+
+    /// Zeroes a template slot and reads it back, so deletion is provable
+    /// rather than assumed. A write-protected slot fails the readback.
+    pub fn erase_template(&mut self, slot: u32) -> Result<(), &'static str> {
+        if slot >= FP_MAX_TEMPLATE_SLOTS {
+            return Err("Template slot out of range");
+        }
+        let was_enrolled = !self.slot_is_clear(slot);
+        for word in 0..FP_TEMPLATE_SLOT_WORDS {
+            self.regs.write32(Self::slot_offset(slot, word), 0);
+        }
+        if !self.slot_is_clear(slot) {
+            return Err("Template erase verification failed");
+        }
+        if was_enrolled {
+            let count = self.template_count();
+            self.regs.write32(FP_TEMPLATE_OFFSET, count.saturating_sub(1));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use crate::device_interfaces::mmio::test_support::MemoryBank;
+    use alloc::collections::BTreeSet;
+
+    struct MockBank {
+        mem: MemoryBank,
+        write_protected: BTreeSet<u64>,
+    }
+
+    impl MockBank {
+        fn new() -> Self {
+            MockBank { mem: MemoryBank::default(), write_protected: BTreeSet::new() }
+        }
+
+        fn enroll(&mut self, slot: u32) {
+            for word in 0..FP_TEMPLATE_SLOT_WORDS {
+                self.mem.set(Fingerprint::<MockBank>::slot_offset(slot, word), 0xA5A5_0000 | word as u32);
+            }
+            self.mem.set(FP_TEMPLATE_OFFSET, self.mem.get(FP_TEMPLATE_OFFSET) + 1);
+        }
+
+        fn lock(&mut self, slot: u32) {
+            for word in 0..FP_TEMPLATE_SLOT_WORDS {
+                self.write_protected.insert(Fingerprint::<MockBank>::slot_offset(slot, word));
+            }
+        }
+    }
+
+    impl RegisterBank for MockBank {
+        fn read32(&self, offset: u64) -> u32 {
+            self.mem.get(offset)
+        }
+
+        fn write32(&self, offset: u64, value: u32) {
+            if !self.write_protected.contains(&offset) {
+                self.mem.set(offset, value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_erase_clears_slot_and_decrements_count() {
+        let mut bank = MockBank::new();
+        bank.enroll(0);
+        bank.enroll(3);
+        let mut fp = Fingerprint::with_registers(bank);
+        assert_eq!(fp.template_count(), 2);
+        assert!(fp.erase_template(3).is_ok());
+        assert!(fp.slot_is_clear(3));
+        assert!(!fp.slot_is_clear(0));
+        assert_eq!(fp.template_count(), 1);
+        assert!(fp.erase_template(3).is_ok());
+        assert_eq!(fp.template_count(), 1);
+    }
+
+    #[test]
+    fn test_locked_slot_reports_error() {
+        let mut bank = MockBank::new();
+        bank.enroll(1);
+        bank.lock(1);
+        let mut fp = Fingerprint::with_registers(bank);
+        assert_eq!(fp.erase_template(1), Err("Template erase verification failed"));
+        assert_eq!(fp.template_count(), 1);
+        assert_eq!(fp.erase_template(FP_MAX_TEMPLATE_SLOTS), Err("Template slot out of range"));
+    }
+}
@@ -1,5 +1,9 @@
 use core::ptr::{read_volatile, write_volatile};
 
+use super::{
+    liveness_gated_match, BiometricSample, DefaultLivenessCheck, LivenessCheck, MmioRegisters, RegisterBank,
+};
+
 const IRIS_CTRL_OFFSET: u64 = 0x0000;
 const IRIS_STATUS_OFFSET: u64 = 0x0004;
 const IRIS_ENROLL_OFFSET: u64 = 0x0008;
@@ -8,6 +12,7 @@ const IRIS_CONF_OFFSET: u64 = 0x0010;
 const IRIS_ATTEMPTS_OFFSET: u64 = 0x0014;
 const IRIS_LOCK_OFFSET: u64 = 0x0018;
 const IRIS_DATA_OFFSET: u64 = 0x001C;
+pub const IRIS_DEFAULT_THRESHOLD: u32 = 80;
 
 fn iris_reg(offset: u64) -> u64 {
     crate::iris_base() + offset
@@ -88,3 +93,56 @@ pub fn write_data(data: u32) -> Result<(), &'static str> {
     }
     Ok(())
 }
+
+pub struct Iris<R: RegisterBank = MmioRegisters, L: LivenessCheck = DefaultLivenessCheck> {
+    regs: R,
+    liveness: L,
+    threshold: u32,
+}
+
+impl Iris {
+    pub fn new() -> Self {
+        Iris::with_registers(MmioRegisters::new(crate::iris_base()))
+    }
+}
+
+impl<R: RegisterBank> Iris<R> {
+    pub fn with_registers(regs: R) -> Self {
+        Iris::with_liveness(regs, DefaultLivenessCheck)
+    }
+}
+
+impl<R: RegisterBank, L: LivenessCheck> Iris<R, L> {
+    pub fn with_liveness(regs: R, liveness: L) -> Self {
+        Iris { regs, liveness, threshold: IRIS_DEFAULT_THRESHOLD }
+    }
+
+    pub fn set_threshold(&mut self, threshold: u32) {
+        self.threshold = threshold;
+    }
+
+    /// Grants a match only if the liveness check reports `Live` and the
+    /// matcher score reaches the threshold; returns the score.
+    pub fn authenticate(&mut self, sample: &BiometricSample) -> Result<u32, &'static str> {
+        liveness_gated_match(&self.regs, &self.liveness, IRIS_VERIFY_OFFSET, self.threshold, sample)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biometric::test_support::{check_default_liveness, check_liveness_gating, FixedLiveness, ScoredBank};
+
+    #[test]
+    fn test_match_is_gated_on_liveness() {
+        check_liveness_gating(|score, liveness, sample| {
+            Iris::with_liveness(ScoredBank::new(IRIS_VERIFY_OFFSET, score), FixedLiveness(liveness)).authenticate(sample)
+        });
+    }
+
+    #[test]
+    fn test_default_check_rejects_flat_capture() {
+        let mut iris = Iris::with_registers(ScoredBank::new(IRIS_VERIFY_OFFSET, 99));
+        check_default_liveness(|sample| iris.authenticate(sample));
+    }
+}
@@ -6,3 +6,149 @@ pub use fingerprint::Fingerprint;
 pub use faceid::FaceID;
 pub use iris::Iris;
 pub use voice_biometrics::VoiceBiometrics;
+
+pub use crate::device_interfaces::mmio::{MmioRegisters, RegisterBank};
+
+pub const LIVENESS_MIN_SAMPLE_LEN: usize = 8;
+
+/// A probe handed to a matcher, together with the raw capture the liveness
+/// check inspects (frames for iris, audio for voice).
+#[derive(Debug, Clone, Copy)]
+pub struct BiometricSample<'a> {
+    pub probe: u32,
+    pub capture: &'a [u8],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Liveness {
+    Live,
+    Spoof,
+    Inconclusive,
+}
+
+pub trait LivenessCheck {
+    fn assess(&self, sample: &BiometricSample) -> Liveness;
+}
+
+/// Minimal sanity filter, not real liveness detection: it only rejects
+/// captures in which every byte is identical (a dead or saturated sensor, a
+/// blank frame) and reports too little data as inconclusive. Anything with
+/// variation passes, so products needing spoof resistance must supply their
+/// own `LivenessCheck`.
+pub struct DefaultLivenessCheck;
+
+impl LivenessCheck for DefaultLivenessCheck {
+    fn assess(&self, sample: &BiometricSample) -> Liveness {
+        let capture = sample.capture;
+        if capture.len() < LIVENESS_MIN_SAMPLE_LEN {
+            return Liveness::Inconclusive;
+        }
+        if capture.iter().all(|&b| b == capture[0]) {
+            return Liveness::Spoof;
+        }
+        Liveness::Live
+    }
+}
+
+/// Runs `liveness` first and only then the hardware matcher at
+/// `verify_offset`, returning the match score once both pass.
+fn liveness_gated_match<R: RegisterBank, L: LivenessCheck>(
+    regs: &R,
+    liveness: &L,
+    verify_offset: u64,
+    threshold: u32,
+    sample: &BiometricSample,
+) -> Result<u32, &'static str> {
+    match liveness.assess(sample) {
+        Liveness::Live => {}
+        Liveness::Spoof => return Err("Liveness check failed: spoof detected"),
+        Liveness::Inconclusive => return Err("Liveness check inconclusive"),
+    }
+    regs.write32(verify_offset, sample.probe);
+    let score = regs.read32(verify_offset);
+    if score < threshold {
+        return Err("Biometric match rejected");
+    }
+    Ok(score)
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    extern crate alloc;
+    use super::*;
+    use alloc::collections::BTreeMap;
+    use core::cell::RefCell;
+
+    /// Register bank whose verify register always reads back `score`.
+    pub struct ScoredBank {
+        pub regs: RefCell<BTreeMap<u64, u32>>,
+        pub verify_offset: u64,
+        pub score: u32,
+    }
+
+    impl ScoredBank {
+        pub fn new(verify_offset: u64, score: u32) -> Self {
+            ScoredBank { regs: RefCell::new(BTreeMap::new()), verify_offset, score }
+        }
+    }
+
+    impl RegisterBank for ScoredBank {
+        fn read32(&self, offset: u64) -> u32 {
+            if offset == self.verify_offset {
+                return self.score;
+            }
+            self.regs.borrow().get(&offset).copied().unwrap_or(0)
+        }
+
+        fn write32(&self, offset: u64, value: u32) {
+            self.regs.borrow_mut().insert(offset, value);
+        }
+    }
+
+    pub struct FixedLiveness(pub Liveness);
+
+    impl LivenessCheck for FixedLiveness {
+        fn assess(&self, _sample: &BiometricSample) -> Liveness {
+            self.0
+        }
+    }
+
+    pub const VARIED_CAPTURE: [u8; 8] = [3, 9, 4, 12, 7, 1, 8, 5];
+
+    /// Shared expectations for a liveness-gated matcher. `authenticate`
+    /// builds the matcher with the given hardware score and liveness verdict
+    /// and runs it on the sample.
+    pub fn check_liveness_gating(
+        authenticate: impl Fn(u32, Liveness, &BiometricSample) -> Result<u32, &'static str>,
+    ) {
+        let sample = BiometricSample { probe: 1, capture: &VARIED_CAPTURE };
+        assert_eq!(authenticate(95, Liveness::Live, &sample), Ok(95));
+        assert!(authenticate(10, Liveness::Live, &sample).is_err());
+        assert_eq!(authenticate(99, Liveness::Spoof, &sample), Err("Liveness check failed: spoof detected"));
+        assert_eq!(authenticate(99, Liveness::Inconclusive, &sample), Err("Liveness check inconclusive"));
+    }
+
+    /// `authenticate` runs a matcher using `DefaultLivenessCheck` whose
+    /// hardware score is 99.
+    pub fn check_default_liveness(mut authenticate: impl FnMut(&BiometricSample) -> Result<u32, &'static str>) {
+        let flat = [0u8; 16];
+        assert!(authenticate(&BiometricSample { probe: 1, capture: &flat }).is_err());
+        assert_eq!(authenticate(&BiometricSample { probe: 1, capture: &VARIED_CAPTURE }), Ok(99));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_liveness_check() {
+        let check = DefaultLivenessCheck;
+        let live = [3u8, 9, 4, 12, 7, 1, 8, 5];
+        let flat = [7u8; 16];
+        let short = [1u8, 2, 3];
+        assert_eq!(check.assess(&BiometricSample { probe: 0, capture: &live }), Liveness::Live);
+        assert_eq!(check.assess(&BiometricSample { probe: 0, capture: &flat }), Liveness::Spoof);
+        assert_eq!(check.assess(&BiometricSample { probe: 0, capture: &short }), Liveness::Inconclusive);
+    }
+}
@@ -1,5 +1,9 @@
 use core::ptr::{read_volatile, write_volatile};
 
+use super::{
+    liveness_gated_match, BiometricSample, DefaultLivenessCheck, LivenessCheck, MmioRegisters, RegisterBank,
+};
+
 const VOICE_CTRL_OFFSET: u64 = 0x0000;
 const VOICE_STATUS_OFFSET: u64 = 0x0004;
 const VOICE_ENROLL_OFFSET: u64 = 0x0008;
@@ -8,6 +12,7 @@ const VOICE_PROFILE_OFFSET: u64 = 0x0010;
 const VOICE_CONF_OFFSET: u64 = 0x0014;
 const VOICE_DATA_OFFSET: u64 = 0x0018;
 const VOICE_CONFIG_OFFSET: u64 = 0x001C;
+pub const VOICE_DEFAULT_THRESHOLD: u32 = 80;
 
 fn voice_reg(offset: u64) -> u64 {
     crate::voice_base() + offset
@@ -102,4 +107,57 @@ pub fn get_config() -> u32 {
 }
 pub fn enroll(profile_id: u32) -> Result<(), &'static str> {
     enroll_voice(profile_id)
-}
\ No newline at end of file
+}
+
+pub struct VoiceBiometrics<R: RegisterBank = MmioRegisters, L: LivenessCheck = DefaultLivenessCheck> {
+    regs: R,
+    liveness: L,
+    threshold: u32,
+}
+
+impl VoiceBiometrics {
+    pub fn new() -> Self {
+        VoiceBiometrics::with_registers(MmioRegisters::new(crate::voice_base()))
+    }
+}
+
+impl<R: RegisterBank> VoiceBiometrics<R> {
+    pub fn with_registers(regs: R) -> Self {
+        VoiceBiometrics::with_liveness(regs, DefaultLivenessCheck)
+    }
+}
+
+impl<R: RegisterBank, L: LivenessCheck> VoiceBiometrics<R, L> {
+    pub fn with_liveness(regs: R, liveness: L) -> Self {
+        VoiceBiometrics { regs, liveness, threshold: VOICE_DEFAULT_THRESHOLD }
+    }
+
+    pub fn set_threshold(&mut self, threshold: u32) {
+        self.threshold = threshold;
+    }
+
+    /// Grants a match only if the liveness check reports `Live` and the
+    /// matcher score reaches the threshold; returns the score.
+    pub fn authenticate(&mut self, sample: &BiometricSample) -> Result<u32, &'static str> {
+        liveness_gated_match(&self.regs, &self.liveness, VOICE_VERIFY_OFFSET, self.threshold, sample)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biometric::test_support::{check_default_liveness, check_liveness_gating, FixedLiveness, ScoredBank};
+
+    #[test]
+    fn test_match_is_gated_on_liveness() {
+        check_liveness_gating(|score, liveness, sample| {
+            VoiceBiometrics::with_liveness(ScoredBank::new(VOICE_VERIFY_OFFSET, score), FixedLiveness(liveness)).authenticate(sample)
+        });
+    }
+
+    #[test]
+    fn test_default_check_rejects_flat_capture() {
+        let mut voice = VoiceBiometrics::with_registers(ScoredBank::new(VOICE_VERIFY_OFFSET, 99));
+        check_default_liveness(|sample| voice.authenticate(sample));
+    }
+}
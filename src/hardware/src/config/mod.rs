@@ -420,6 +420,8 @@ pub struct PowerConfig {
     pub battery_capacity_mah: u32,
     pub fast_charging_enabled: bool,
     pub wireless_charging_enabled: bool,
+    pub max_charge_current_ma: u16,
+    pub max_charge_voltage_mv: u16,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -498,6 +500,8 @@ impl HardwareConfig {
                 battery_capacity_mah: 5000,
                 fast_charging_enabled: true,
                 wireless_charging_enabled: true,
+                max_charge_current_ma: 3000,
+                max_charge_voltage_mv: 4400,
             },
             display: DisplayConfig {
                 resolution_width: 1440,
@@ -1,6 +1,7 @@
 /// GPIO (General Purpose Input/Output) Interface
 
 use core::ptr::{read_volatile, write_volatile};
+use super::mmio::{MmioRegisters, RegisterBank};
 
 #[derive(Clone, Debug, PartialEq, Copy)]
 pub enum GPIOMode {
@@ -132,7 +133,96 @@ impl Default for GPIOInterface {
     }
 }
 
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Rising = 1,
+    Falling = 2,
+    Both = 3,
+}
+
+/// Edge interrupts for the 32 pins of one GPIO bank. Each pin has a 2-bit
+/// trigger field (0 = disabled) split over two mode registers; the pending
+/// register is write-one-to-clear.
+pub struct GpioController<R: RegisterBank = MmioRegisters> {
+    regs: R,
+}
+
+impl GpioController {
+    pub fn new(bank: u8) -> Self {
+        let base = crate::gpio_base() + bank as u64 * GPIO_BANK_STRIDE;
+        GpioController::with_registers(MmioRegisters::new(base))
+    }
+}
+
+impl<R: RegisterBank> GpioController<R> {
+    pub fn with_registers(regs: R) -> Self {
+        GpioController { regs }
+    }
+
+    fn mode_slot(pin: u8) -> Result<(u64, u32), &'static str> {
+        if pin as u32 >= GPIO_BANK_WIDTH {
+            return Err("invalid_pin");
+        }
+        let offset = if pin < 16 { GPIO_INT_MODE_LO_OFFSET } else { GPIO_INT_MODE_HI_OFFSET };
+        Ok((offset, ((pin as u32) % 16) * 2))
+    }
+
+    fn write_trigger(&mut self, pin: u8, bits: u32) -> Result<(), &'static str> {
+        let (offset, shift) = Self::mode_slot(pin)?;
+        let mut current = self.regs.read32(offset);
+        current &= !(0x3 << shift);
+        current |= bits << shift;
+        self.regs.write32(offset, current);
+        Ok(())
+    }
+
+    pub fn configure_interrupt(&mut self, pin: u8, edge: Edge) -> Result<(), &'static str> {
+        self.write_trigger(pin, edge as u32)
+    }
+
+    pub fn disable_interrupt(&mut self, pin: u8) -> Result<(), &'static str> {
+        self.write_trigger(pin, 0)
+    }
+
+    pub fn interrupt_edge(&self, pin: u8) -> Result<Option<Edge>, &'static str> {
+        let (offset, shift) = Self::mode_slot(pin)?;
+        Ok(match (self.regs.read32(offset) >> shift) & 0x3 {
+            1 => Some(Edge::Rising),
+            2 => Some(Edge::Falling),
+            3 => Some(Edge::Both),
+            _ => None,
+        })
+    }
+
+    /// Pins with an interrupt configured.
+    pub fn configured_mask(&self) -> u32 {
+        let mut mask = 0;
+        for (offset, first_pin) in [(GPIO_INT_MODE_LO_OFFSET, 0), (GPIO_INT_MODE_HI_OFFSET, 16)] {
+            let modes = self.regs.read32(offset);
+            for slot in 0..16 {
+                if (modes >> (slot * 2)) & 0x3 != 0 {
+                    mask |= 1 << (first_pin + slot);
+                }
+            }
+        }
+        mask
+    }
+
+    /// Bitmask of configured pins whose interrupt has fired.
+    pub fn poll_pending(&self) -> u32 {
+        self.regs.read32(GPIO_INT_PENDING_OFFSET) & self.configured_mask()
+    }
+
+    pub fn clear_pending(&mut self, mask: u32) {
+        self.regs.write32(GPIO_INT_PENDING_OFFSET, mask);
+    }
+}
+
 const GPIO_BANK_STRIDE: u64 = 0x20;
+const GPIO_BANK_WIDTH: u32 = 32;
+const GPIO_INT_MODE_LO_OFFSET: u64 = 0x0014;
+const GPIO_INT_MODE_HI_OFFSET: u64 = 0x0018;
+const GPIO_INT_PENDING_OFFSET: u64 = 0x001C;
 
 fn gpio_bank_reg(base: u64, pin: u8) -> u64 {
     let bank = (pin as u64) / 32;
@@ -190,3 +280,65 @@ fn read_mode(reg: u64, pin: u8) -> GPIOMode {
         _ => GPIOMode::Analog,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    #[derive(Default)]
+    struct MockBank {
+        mode_lo: Cell<u32>,
+        mode_hi: Cell<u32>,
+        pending: Cell<u32>,
+    }
+
+    impl RegisterBank for MockBank {
+        fn read32(&self, offset: u64) -> u32 {
+            match offset {
+                GPIO_INT_MODE_LO_OFFSET => self.mode_lo.get(),
+                GPIO_INT_MODE_HI_OFFSET => self.mode_hi.get(),
+                GPIO_INT_PENDING_OFFSET => self.pending.get(),
+                _ => 0,
+            }
+        }
+
+        fn write32(&self, offset: u64, value: u32) {
+            match offset {
+                GPIO_INT_MODE_LO_OFFSET => self.mode_lo.set(value),
+                GPIO_INT_MODE_HI_OFFSET => self.mode_hi.set(value),
+                GPIO_INT_PENDING_OFFSET => self.pending.set(self.pending.get() & !value),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_configure_interrupt_sets_mode_bits() {
+        let mut gpio = GpioController::with_registers(MockBank::default());
+        gpio.configure_interrupt(0, Edge::Rising).unwrap();
+        gpio.configure_interrupt(5, Edge::Both).unwrap();
+        gpio.configure_interrupt(17, Edge::Falling).unwrap();
+        assert_eq!(gpio.regs.mode_lo.get(), 0b01 | (0b11 << 10));
+        assert_eq!(gpio.regs.mode_hi.get(), 0b10 << 2);
+        assert_eq!(gpio.interrupt_edge(5), Ok(Some(Edge::Both)));
+        assert_eq!(gpio.interrupt_edge(6), Ok(None));
+        gpio.configure_interrupt(5, Edge::Falling).unwrap();
+        assert_eq!(gpio.interrupt_edge(5), Ok(Some(Edge::Falling)));
+        assert_eq!(gpio.configure_interrupt(32, Edge::Rising), Err("invalid_pin"));
+    }
+
+    #[test]
+    fn test_pending_reports_configured_pins_only() {
+        let mut gpio = GpioController::with_registers(MockBank::default());
+        gpio.configure_interrupt(3, Edge::Rising).unwrap();
+        gpio.configure_interrupt(20, Edge::Both).unwrap();
+        gpio.regs.pending.set((1 << 3) | (1 << 4) | (1 << 20));
+        assert_eq!(gpio.poll_pending(), (1 << 3) | (1 << 20));
+
+        gpio.clear_pending(1 << 3);
+        assert_eq!(gpio.poll_pending(), 1 << 20);
+        gpio.disable_interrupt(20).unwrap();
+        assert_eq!(gpio.poll_pending(), 0);
+    }
+}
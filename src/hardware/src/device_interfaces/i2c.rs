@@ -1,3 +1,5 @@
+use super::mmio::{MmioRegisters, RegisterBank};
+
 #[repr(u8)]
 pub enum I2CCommand {
     Start = 0x01,
@@ -19,8 +21,37 @@ pub trait I2CBus {
     fn read(&mut self, address: u8, buf: &mut [u8]) -> Result<(), &'static str>;
 }
 
-pub struct I2CInterface {
-    controller_id: u8,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum I2cError {
+    /// The addressed device did not acknowledge its address or a data byte.
+    Nak,
+    ArbitrationLost,
+    TxTimeout,
+    RxTimeout,
+    /// The bus never went idle, e.g. a peripheral holding SDA low.
+    BusBusy,
+}
+
+impl I2cError {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            I2cError::Nak => "nack",
+            I2cError::ArbitrationLost => "arb_lost",
+            I2cError::TxTimeout => "tx_timeout",
+            I2cError::RxTimeout => "rx_timeout",
+            I2cError::BusBusy => "bus_busy",
+        }
+    }
+}
+
+impl From<I2cError> for &'static str {
+    fn from(err: I2cError) -> Self {
+        err.as_str()
+    }
+}
+
+pub struct I2CInterface<R: RegisterBank = MmioRegisters> {
+    regs: R,
 }
 
 impl I2CInterface {
@@ -28,87 +59,87 @@ impl I2CInterface {
         if controller_id >= 16 {
             return Err("invalid_i2c_controller");
         }
-        Ok(I2CInterface { controller_id })
-    }
-
-    fn base(&self) -> u64 {
-        crate::i2c_base() + (self.controller_id as u64 * I2C_CTRL_STRIDE)
+        let base = crate::i2c_base() + (controller_id as u64 * I2C_CTRL_STRIDE);
+        Ok(I2CInterface::with_registers(MmioRegisters::new(base)))
     }
+}
 
-    fn reg(&self, offset: u64) -> u64 {
-        self.base() + offset
+impl<R: RegisterBank> I2CInterface<R> {
+    pub fn with_registers(regs: R) -> Self {
+        I2CInterface { regs }
     }
 
     fn status(&self) -> u32 {
-        unsafe { core::ptr::read_volatile(self.reg(I2C_STATUS_OFFSET) as *const u32) }
+        self.regs.read32(I2C_STATUS_OFFSET)
     }
 
-    fn wait_tx_ready(&self) -> Result<(), &'static str> {
+    fn wait_tx_ready(&self) -> Result<(), I2cError> {
         for _ in 0..I2C_POLL_LIMIT {
             let status = self.status();
             if status & I2C_STATUS_ARB_LOST != 0 {
-                return Err("arb_lost");
+                return Err(I2cError::ArbitrationLost);
             }
             if status & I2C_STATUS_TX_READY != 0 {
                 return Ok(());
             }
         }
-        Err("tx_timeout")
+        Err(I2cError::TxTimeout)
     }
 
-    fn wait_rx_ready(&self) -> Result<(), &'static str> {
+    fn wait_rx_ready(&self) -> Result<(), I2cError> {
         for _ in 0..I2C_POLL_LIMIT {
             let status = self.status();
             if status & I2C_STATUS_ARB_LOST != 0 {
-                return Err("arb_lost");
+                return Err(I2cError::ArbitrationLost);
             }
             if status & I2C_STATUS_RX_READY != 0 {
                 return Ok(());
             }
         }
-        Err("rx_timeout")
+        Err(I2cError::RxTimeout)
     }
 
-    fn wait_idle(&self) -> Result<(), &'static str> {
+    fn wait_idle(&self) -> Result<(), I2cError> {
         for _ in 0..I2C_POLL_LIMIT {
             if self.status() & I2C_STATUS_BUSY == 0 {
                 return Ok(());
             }
         }
-        Err("bus_busy")
+        Err(I2cError::BusBusy)
     }
 
     fn issue_cmd(&self, cmd: I2CCommand) {
-        unsafe {
-            core::ptr::write_volatile(self.reg(I2C_CMD_OFFSET) as *mut u32, cmd as u32);
-            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
-        }
+        self.regs.write32(I2C_CMD_OFFSET, cmd as u32);
     }
 
-    fn write_byte(&self, byte: u8) -> Result<(), &'static str> {
+    fn write_byte(&self, byte: u8) -> Result<(), I2cError> {
         self.wait_tx_ready()?;
-        unsafe {
-            core::ptr::write_volatile(self.reg(I2C_TX_OFFSET) as *mut u32, byte as u32);
-        }
+        self.regs.write32(I2C_TX_OFFSET, byte as u32);
         self.issue_cmd(I2CCommand::Write);
         if self.status() & I2C_STATUS_NACK != 0 {
-            return Err("nack");
+            return Err(I2cError::Nak);
         }
         Ok(())
     }
 
-    fn read_byte(&self) -> Result<u8, &'static str> {
+    fn read_byte(&self) -> Result<u8, I2cError> {
         self.issue_cmd(I2CCommand::Read);
         self.wait_rx_ready()?;
-        let value = unsafe { core::ptr::read_volatile(self.reg(I2C_RX_OFFSET) as *const u32) };
-        Ok(value as u8)
+        Ok(self.regs.read32(I2C_RX_OFFSET) as u8)
+    }
+
+    /// Runs `body` between START and STOP, issuing STOP even when the
+    /// transfer fails so the bus is released for the next transaction.
+    fn transaction<T>(&self, body: impl FnOnce(&Self) -> Result<T, I2cError>) -> Result<T, I2cError> {
+        self.wait_idle()?;
+        self.issue_cmd(I2CCommand::Start);
+        let result = body(self);
+        self.issue_cmd(I2CCommand::Stop);
+        result
     }
 
     pub fn enable(&self) -> Result<(), &'static str> {
-        unsafe {
-            core::ptr::write_volatile(self.reg(I2C_CTRL_OFFSET) as *mut u32, I2C_CTRL_ENABLE);
-            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
-        }
+        self.regs.write32(I2C_CTRL_OFFSET, I2C_CTRL_ENABLE);
         Ok(())
     }
 
@@ -117,10 +148,7 @@ impl I2CInterface {
             return Err("frequency_too_high");
         }
         let divider = (I2C_REF_CLOCK_KHZ / (freq_khz * 2)).max(1);
-        unsafe {
-            core::ptr::write_volatile(self.reg(I2C_CLKDIV_OFFSET) as *mut u32, divider);
-            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
-        }
+        self.regs.write32(I2C_CLKDIV_OFFSET, divider);
         Ok(())
     }
 
@@ -128,32 +156,52 @@ impl I2CInterface {
         if data.is_empty() {
             return Ok(());
         }
-        self.wait_idle()?;
-        self.issue_cmd(I2CCommand::Start);
-        self.write_byte(address << 1)?;
-        for byte in data {
-            self.write_byte(*byte)?;
-        }
-        self.issue_cmd(I2CCommand::Stop);
-        Ok(())
+        self.transaction(|bus| {
+            bus.write_byte(address << 1)?;
+            for byte in data {
+                bus.write_byte(*byte)?;
+            }
+            Ok(())
+        })
+        .map_err(I2cError::as_str)
     }
 
     pub fn read(&self, address: u8, buf: &mut [u8]) -> Result<(), &'static str> {
         if buf.is_empty() {
             return Ok(());
         }
-        self.wait_idle()?;
-        self.issue_cmd(I2CCommand::Start);
-        self.write_byte((address << 1) | 0x1)?;
-        for slot in buf.iter_mut() {
-            *slot = self.read_byte()?;
-        }
-        self.issue_cmd(I2CCommand::Stop);
-        Ok(())
+        self.transaction(|bus| {
+            bus.write_byte((address << 1) | 0x1)?;
+            for slot in buf.iter_mut() {
+                *slot = bus.read_byte()?;
+            }
+            Ok(())
+        })
+        .map_err(I2cError::as_str)
+    }
+
+    pub fn write_reg(&self, address: u8, reg: u8, value: u8) -> Result<(), I2cError> {
+        self.transaction(|bus| {
+            bus.write_byte(address << 1)?;
+            bus.write_byte(reg)?;
+            bus.write_byte(value)
+        })
+    }
+
+    /// Writes the register pointer, then reads one byte back after a
+    /// repeated START.
+    pub fn read_reg(&self, address: u8, reg: u8) -> Result<u8, I2cError> {
+        self.transaction(|bus| {
+            bus.write_byte(address << 1)?;
+            bus.write_byte(reg)?;
+            bus.issue_cmd(I2CCommand::Start);
+            bus.write_byte((address << 1) | 0x1)?;
+            bus.read_byte()
+        })
     }
 }
 
-impl I2CBus for I2CInterface {
+impl<R: RegisterBank> I2CBus for I2CInterface<R> {
     fn write(&mut self, address: u8, data: &[u8]) -> Result<(), &'static str> {
         I2CInterface::write(self, address, data)
     }
@@ -187,3 +235,125 @@ const I2C_STATUS_ARB_LOST: u32 = 0x0010;
 
 const I2C_REF_CLOCK_KHZ: u32 = 26_000;
 const I2C_POLL_LIMIT: u32 = 100_000;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::{Cell, RefCell};
+
+    /// Controller model with a single register-file device behind it.
+    struct MockController {
+        device_addr: u8,
+        device_regs: RefCell<[u8; 16]>,
+        hung: bool,
+        tx: Cell<u8>,
+        rx: Cell<Option<u8>>,
+        expect_address: Cell<bool>,
+        pointer: Cell<Option<u8>>,
+        nack: Cell<bool>,
+        stops: Cell<u32>,
+    }
+
+    impl MockController {
+        fn new(device_addr: u8) -> Self {
+            MockController {
+                device_addr,
+                device_regs: RefCell::new([0; 16]),
+                hung: false,
+                tx: Cell::new(0),
+                rx: Cell::new(None),
+                expect_address: Cell::new(false),
+                pointer: Cell::new(None),
+                nack: Cell::new(false),
+                stops: Cell::new(0),
+            }
+        }
+    }
+
+    impl RegisterBank for MockController {
+        fn read32(&self, offset: u64) -> u32 {
+            match offset {
+                I2C_STATUS_OFFSET if self.hung => I2C_STATUS_BUSY,
+                I2C_STATUS_OFFSET => {
+                    let mut status = I2C_STATUS_TX_READY;
+                    if self.rx.get().is_some() {
+                        status |= I2C_STATUS_RX_READY;
+                    }
+                    if self.nack.get() {
+                        status |= I2C_STATUS_NACK;
+                    }
+                    status
+                }
+                I2C_RX_OFFSET => self.rx.take().unwrap_or(0) as u32,
+                _ => 0,
+            }
+        }
+
+        fn write32(&self, offset: u64, value: u32) {
+            match offset {
+                I2C_TX_OFFSET => self.tx.set(value as u8),
+                I2C_CMD_OFFSET if value == I2CCommand::Start as u32 => {
+                    self.expect_address.set(true);
+                    self.nack.set(false);
+                }
+                I2C_CMD_OFFSET if value == I2CCommand::Stop as u32 => {
+                    self.pointer.set(None);
+                    self.stops.set(self.stops.get() + 1);
+                }
+                I2C_CMD_OFFSET if value == I2CCommand::Write as u32 => {
+                    let tx = self.tx.get();
+                    if self.expect_address.replace(false) {
+                        self.nack.set(tx >> 1 != self.device_addr);
+                    } else if let Some(reg) = self.pointer.get() {
+                        self.device_regs.borrow_mut()[reg as usize] = tx;
+                        self.pointer.set(Some(reg + 1));
+                    } else {
+                        self.pointer.set(Some(tx));
+                    }
+                }
+                I2C_CMD_OFFSET if value == I2CCommand::Read as u32 => {
+                    let reg = self.pointer.get().unwrap_or(0);
+                    self.rx.set(Some(self.device_regs.borrow()[reg as usize]));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_register_round_trip_on_responsive_device() {
+        let bus = I2CInterface::with_registers(MockController::new(0x34));
+        bus.write_reg(0x34, 0x05, 0xA7).unwrap();
+        assert_eq!(bus.regs.device_regs.borrow()[0x05], 0xA7);
+        bus.regs.device_regs.borrow_mut()[0x09] = 0x42;
+        assert_eq!(bus.read_reg(0x34, 0x09), Ok(0x42));
+    }
+
+    #[test]
+    fn test_absent_device_naks() {
+        let mut bus = I2CInterface::with_registers(MockController::new(0x34));
+        assert_eq!(bus.write_reg(0x50, 0x01, 0xFF), Err(I2cError::Nak));
+        assert_eq!(bus.read_reg(0x50, 0x01), Err(I2cError::Nak));
+        assert_eq!(*bus.regs.device_regs.borrow(), [0; 16]);
+
+        let stops = bus.regs.stops.get();
+        assert_eq!(I2CBus::write(&mut bus, 0x50, &[0x01]), Err("nack"));
+        assert_eq!(bus.regs.stops.get(), stops + 1);
+        let mut buf = [0u8; 1];
+        assert_eq!(I2CBus::read(&mut bus, 0x50, &mut buf), Err("nack"));
+        assert_eq!(bus.regs.stops.get(), stops + 2);
+
+        assert_eq!(I2CBus::write(&mut bus, 0x34, &[0x00, 0x5A]), Ok(()));
+        assert_eq!(I2CBus::read(&mut bus, 0x34, &mut buf), Ok(()));
+        assert_eq!(buf, [0x5A]);
+    }
+
+    #[test]
+    fn test_hung_bus_times_out() {
+        let mut controller = MockController::new(0x34);
+        controller.hung = true;
+        let bus = I2CInterface::with_registers(controller);
+        assert_eq!(bus.read_reg(0x34, 0x00), Err(I2cError::BusBusy));
+        assert_eq!(bus.write_reg(0x34, 0x00, 0x01), Err(I2cError::BusBusy));
+    }
+}
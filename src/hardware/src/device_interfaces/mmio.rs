@@ -0,0 +1,124 @@
+use core::ptr::{read_volatile, write_volatile};
+
+/// 32-bit register access by offset, so driver logic can be exercised
+/// against an in-memory bank instead of MMIO. Every driver with a register
+/// seam takes one of these; 64-bit registers default to two 32-bit
+/// accesses, low word first.
+pub trait RegisterBank {
+    fn read32(&self, offset: u64) -> u32;
+    fn write32(&self, offset: u64, value: u32);
+
+    fn read64(&self, offset: u64) -> u64 {
+        self.read32(offset) as u64 | (self.read32(offset + 4) as u64) << 32
+    }
+
+    fn write64(&self, offset: u64, value: u64) {
+        self.write32(offset, value as u32);
+        self.write32(offset + 4, (value >> 32) as u32);
+    }
+}
+
+/// A contiguous register block at `base`.
+pub struct MmioRegisters {
+    base: u64,
+}
+
+impl MmioRegisters {
+    pub const fn new(base: u64) -> Self {
+        MmioRegisters { base }
+    }
+}
+
+impl RegisterBank for MmioRegisters {
+    fn read32(&self, offset: u64) -> u32 {
+        unsafe { read_volatile((self.base + offset) as *const u32) }
+    }
+
+    fn write32(&self, offset: u64, value: u32) {
+        unsafe {
+            write_volatile((self.base + offset) as *mut u32, value);
+            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    fn read64(&self, offset: u64) -> u64 {
+        unsafe { read_volatile((self.base + offset) as *const u64) }
+    }
+
+    fn write64(&self, offset: u64, value: u64) {
+        unsafe {
+            write_volatile((self.base + offset) as *mut u64, value);
+            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        }
+    }
+}
+
+/// Registers scattered over the map: `map` turns a driver-local offset
+/// into the absolute address from the board configuration.
+pub struct MappedRegisters {
+    map: fn(u64) -> u64,
+}
+
+impl MappedRegisters {
+    pub const fn new(map: fn(u64) -> u64) -> Self {
+        MappedRegisters { map }
+    }
+}
+
+impl RegisterBank for MappedRegisters {
+    fn read32(&self, offset: u64) -> u32 {
+        unsafe { read_volatile((self.map)(offset) as *const u32) }
+    }
+
+    fn write32(&self, offset: u64, value: u32) {
+        unsafe {
+            write_volatile((self.map)(offset) as *mut u32, value);
+            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    fn read64(&self, offset: u64) -> u64 {
+        unsafe { read_volatile((self.map)(offset) as *const u64) }
+    }
+
+    fn write64(&self, offset: u64, value: u64) {
+        unsafe {
+            write_volatile((self.map)(offset) as *mut u64, value);
+            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    extern crate alloc;
+    use super::RegisterBank;
+    use alloc::collections::BTreeMap;
+    use core::cell::RefCell;
+
+    /// Sparse in-memory bank: unwritten registers read as zero.
+    #[derive(Default)]
+    pub struct MemoryBank {
+        pub regs: RefCell<BTreeMap<u64, u32>>,
+    }
+
+    impl MemoryBank {
+        pub fn get(&self, offset: u64) -> u32 {
+            self.regs.borrow().get(&offset).copied().unwrap_or(0)
+        }
+
+        pub fn set(&self, offset: u64, value: u32) {
+            self.regs.borrow_mut().insert(offset, value);
+        }
+    }
+
+    impl RegisterBank for MemoryBank {
+        fn read32(&self, offset: u64) -> u32 {
+            self.get(offset)
+        }
+
+        fn write32(&self, offset: u64, value: u32) {
+            self.set(offset, value);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+pub mod mmio;
 pub mod usb;
 pub mod i2c;
 pub mod spi;
@@ -5,10 +6,11 @@ pub mod uart;
 pub mod gpio;
 pub mod pci;
 pub mod i2c_master;
-pub use usb::USBInterface;
-pub use i2c::{I2CInterface, I2CBus};
-pub use spi::SPIInterface;
+pub use mmio::{RegisterBank, MmioRegisters, MappedRegisters};
+pub use usb::{USBInterface, USBSpeed, UsbController};
+pub use i2c::{I2CInterface, I2CBus, I2cError};
+pub use spi::{SPIInterface, SpiError};
 pub use uart::UARTInterface;
-pub use gpio::GPIOInterface;
-pub use pci::PCIInterface;
+pub use gpio::{GPIOInterface, GpioController, Edge};
+pub use pci::{PCIInterface, PciBus};
 pub use i2c_master::{I2CMaster, BQ27441Reader};
@@ -3,6 +3,7 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use core::ptr::{read_volatile, write_volatile};
 use core::sync::atomic::{AtomicU32, AtomicBool, Ordering};
+use super::mmio::{MappedRegisters, RegisterBank};
 pub struct PCIInterface {
     enabled: AtomicBool,
     device_count: AtomicU32,
@@ -51,12 +52,7 @@ impl PCIInterface {
     }
 
     fn read_config16(&self, bus: u8, device: u8, function: u8, offset: u8) -> Result<u16, String> {
-        let aligned = (offset & 0xFC) as u32;
-        let address = 0x8000_0000
-            | ((bus as u32) << 16)
-            | ((device as u32) << 11)
-            | ((function as u32) << 8)
-            | aligned;
+        let address = config_address(bus, device, function, offset & 0xFC).map_err(String::from)?;
 
         unsafe {
             write_volatile(crate::pci_cfg_addr() as *mut u32, address);
@@ -73,9 +69,100 @@ impl Default for PCIInterface {
     }
 }
 
+/// Composes a configuration-mechanism-#1 address: enable bit, bus,
+/// device, function and a dword-aligned register offset.
+pub fn config_address(bus: u8, device: u8, function: u8, offset: u8) -> Result<u32, &'static str> {
+    if device > PCI_MAX_DEVICE || function > PCI_MAX_FUNCTION {
+        return Err("invalid_bdf");
+    }
+    if offset & 0x3 != 0 {
+        return Err("unaligned_offset");
+    }
+    Ok(PCI_CFG_ENABLE
+        | ((bus as u32) << 16)
+        | ((device as u32) << 11)
+        | ((function as u32) << 8)
+        | offset as u32)
+}
+
+/// Address/data port pair through which configuration space is reached.
+const PCI_CFG_ADDR_OFFSET: u64 = 0x0;
+const PCI_CFG_DATA_OFFSET: u64 = 0x4;
+
+fn pci_cfg_reg(offset: u64) -> u64 {
+    match offset {
+        PCI_CFG_ADDR_OFFSET => crate::pci_cfg_addr(),
+        _ => crate::pci_cfg_data(),
+    }
+}
+
+pub struct PciBus<R: RegisterBank = MappedRegisters> {
+    regs: R,
+}
+
+impl PciBus {
+    pub fn new() -> Self {
+        PciBus::with_registers(MappedRegisters::new(pci_cfg_reg))
+    }
+}
+
+impl Default for PciBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: RegisterBank> PciBus<R> {
+    pub fn with_registers(regs: R) -> Self {
+        PciBus { regs }
+    }
+
+    pub fn read_config(&mut self, bus: u8, device: u8, function: u8, offset: u8) -> Result<u32, &'static str> {
+        let address = config_address(bus, device, function, offset)?;
+        self.regs.write32(PCI_CFG_ADDR_OFFSET, address);
+        Ok(self.regs.read32(PCI_CFG_DATA_OFFSET))
+    }
+
+    pub fn write_config(&mut self, bus: u8, device: u8, function: u8, offset: u8, value: u32) -> Result<(), &'static str> {
+        let address = config_address(bus, device, function, offset)?;
+        self.regs.write32(PCI_CFG_ADDR_OFFSET, address);
+        self.regs.write32(PCI_CFG_DATA_OFFSET, value);
+        Ok(())
+    }
+}
+
 const PCI_VENDOR_ID_OFFSET: u8 = 0x00;
 const PCI_CTRL_ENABLE: u32 = 0x1;
+const PCI_CFG_ENABLE: u32 = 0x8000_0000;
 
 const PCI_MAX_BUS: u8 = 0;
 const PCI_MAX_DEVICE: u8 = 31;
 const PCI_MAX_FUNCTION: u8 = 7;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device_interfaces::mmio::test_support::MemoryBank;
+
+    #[test]
+    fn test_config_address_encoding() {
+        assert_eq!(config_address(0, 0, 0, 0x00), Ok(0x8000_0000));
+        assert_eq!(config_address(0, 31, 7, 0xFC), Ok(0x8000_FFFC));
+        assert_eq!(config_address(1, 2, 3, 0x10), Ok(0x8001_1310));
+        assert_eq!(config_address(0xFF, 0x1F, 0, 0x3C), Ok(0x80FF_F83C));
+        assert_eq!(config_address(0, 32, 0, 0), Err("invalid_bdf"));
+        assert_eq!(config_address(0, 0, 8, 0), Err("invalid_bdf"));
+    }
+
+    #[test]
+    fn test_unaligned_offset_is_rejected() {
+        let mut pci = PciBus::with_registers(MemoryBank::default());
+        assert_eq!(pci.read_config(0, 1, 0, 0x02), Err("unaligned_offset"));
+        assert_eq!(pci.write_config(0, 1, 0, 0x05, 0xDEAD), Err("unaligned_offset"));
+        assert_eq!(pci.regs.get(PCI_CFG_ADDR_OFFSET), 0);
+
+        pci.write_config(0, 1, 0, 0x04, 0x0006).unwrap();
+        assert_eq!(pci.regs.get(PCI_CFG_ADDR_OFFSET), 0x8000_0804);
+        assert_eq!(pci.read_config(0, 1, 0, 0x04), Ok(0x0006));
+    }
+}
@@ -1,54 +1,78 @@
 extern crate alloc;
 use alloc::string::String;
+use alloc::vec;
 use alloc::vec::Vec;
-use core::ptr::{read_volatile, write_volatile};
 use core::sync::atomic::{AtomicU32, AtomicU8, AtomicBool, Ordering};
-pub struct SPIInterface {
+use super::mmio::{MappedRegisters, RegisterBank};
+
+const SPI_CTRL_OFFSET: u64 = 0x00;
+const SPI_STATUS_OFFSET: u64 = 0x04;
+const SPI_TX_OFFSET: u64 = 0x08;
+const SPI_RX_OFFSET: u64 = 0x0C;
+const SPI_CLK_OFFSET: u64 = 0x10;
+
+fn spi_reg(offset: u64) -> u64 {
+    match offset {
+        SPI_CTRL_OFFSET => crate::spi_ctrl(),
+        SPI_STATUS_OFFSET => crate::spi_status(),
+        SPI_TX_OFFSET => crate::spi_tx(),
+        SPI_RX_OFFSET => crate::spi_rx(),
+        _ => crate::spi_clk(),
+    }
+}
+
+pub struct SPIInterface<R: RegisterBank = MappedRegisters> {
+    regs: R,
     clock_mhz: AtomicU32,
     mode: AtomicU8,
     enabled: AtomicBool,
 }
 impl SPIInterface {
     pub fn new() -> Self {
+        SPIInterface::with_registers(MappedRegisters::new(spi_reg))
+    }
+}
+impl<R: RegisterBank> SPIInterface<R> {
+    pub fn with_registers(regs: R) -> Self {
         SPIInterface {
+            regs,
             clock_mhz: AtomicU32::new(10),
             mode: AtomicU8::new(0),
             enabled: AtomicBool::new(false),
         }
     }
     pub fn enable(&self) -> Result<(), String> {
-        unsafe {
-            write_volatile(crate::spi_ctrl() as *mut u32, SPI_CTRL_ENABLE);
-            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
-        }
+        self.regs.write32(SPI_CTRL_OFFSET, SPI_CTRL_ENABLE);
         self.enabled.store(true, Ordering::SeqCst);
         Ok(())
     }
     pub fn transfer(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut out = vec![0u8; data.len()];
+        self.transfer_into(data, &mut out).map_err(|err| String::from(err.as_str()))?;
+        Ok(out)
+    }
+    /// Full-duplex transfer into a caller-provided buffer: byte `i` of `rx`
+    /// is clocked in while byte `i` of `tx` is clocked out.
+    pub fn transfer_into(&self, tx: &[u8], rx: &mut [u8]) -> Result<(), SpiError> {
+        if tx.len() != rx.len() {
+            return Err(SpiError::LengthMismatch);
+        }
         if !self.enabled.load(Ordering::SeqCst) {
-            return Err(String::from("SPI not enabled"));
+            return Err(SpiError::NotEnabled);
         }
-        let mut out = Vec::with_capacity(data.len());
-        for byte in data {
-            self.wait_tx_ready()?;
-            unsafe {
-                write_volatile(crate::spi_tx() as *mut u32, *byte as u32);
-                core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
-            }
-            self.wait_rx_ready()?;
-            let value = unsafe { read_volatile(crate::spi_rx() as *const u32) };
-            out.push(value as u8);
+        for (out, slot) in tx.iter().zip(rx.iter_mut()) {
+            self.wait_status(SPI_STATUS_TX_READY)?;
+            self.regs.write32(SPI_TX_OFFSET, *out as u32);
+            self.wait_status(SPI_STATUS_RX_READY)?;
+            *slot = self.regs.read32(SPI_RX_OFFSET) as u8;
         }
-        Ok(out)
+        Ok(())
     }
     pub fn set_clock(&self, mhz: u32) -> Result<(), String> {
         if mhz > 50 {
             return Err(String::from("Clock too high"));
         }
-        unsafe {
-            write_volatile(crate::spi_clk() as *mut u32, mhz);
-            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
-        }
+        self.regs.write32(SPI_CLK_OFFSET, mhz);
         self.clock_mhz.store(mhz, Ordering::SeqCst);
         Ok(())
     }
@@ -56,13 +80,10 @@ impl SPIInterface {
         if mode > 3 {
             return Err(String::from("Invalid SPI mode"));
         }
-        unsafe {
-            let mut ctrl = read_volatile(crate::spi_ctrl() as *const u32);
-            ctrl &= !SPI_CTRL_MODE_MASK;
-            ctrl |= (mode as u32) << SPI_CTRL_MODE_SHIFT;
-            write_volatile(crate::spi_ctrl() as *mut u32, ctrl);
-            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
-        }
+        let mut ctrl = self.regs.read32(SPI_CTRL_OFFSET);
+        ctrl &= !SPI_CTRL_MODE_MASK;
+        ctrl |= (mode as u32) << SPI_CTRL_MODE_SHIFT;
+        self.regs.write32(SPI_CTRL_OFFSET, ctrl);
         self.mode.store(mode, Ordering::SeqCst);
         Ok(())
     }
@@ -73,24 +94,16 @@ impl SPIInterface {
         self.mode.load(Ordering::SeqCst)
     }
 
-    fn wait_tx_ready(&self) -> Result<(), String> {
-        for _ in 0..SPI_POLL_LIMIT {
-            let status = unsafe { read_volatile(crate::spi_status() as *const u32) };
-            if status & SPI_STATUS_TX_READY != 0 {
-                return Ok(());
-            }
-        }
-        Err(String::from("spi_tx_timeout"))
-    }
-
-    fn wait_rx_ready(&self) -> Result<(), String> {
+    /// Polls until `ready` is set and the shifter is not busy, for at most
+    /// `SPI_POLL_LIMIT` status reads.
+    fn wait_status(&self, ready: u32) -> Result<(), SpiError> {
         for _ in 0..SPI_POLL_LIMIT {
-            let status = unsafe { read_volatile(crate::spi_status() as *const u32) };
-            if status & SPI_STATUS_RX_READY != 0 {
+            let status = self.regs.read32(SPI_STATUS_OFFSET);
+            if status & SPI_STATUS_BUSY == 0 && status & ready != 0 {
                 return Ok(());
             }
         }
-        Err(String::from("spi_rx_timeout"))
+        Err(SpiError::Timeout)
     }
 }
 impl Default for SPIInterface {
@@ -99,11 +112,116 @@ impl Default for SPIInterface {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpiError {
+    NotEnabled,
+    LengthMismatch,
+    Timeout,
+}
+
+impl SpiError {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SpiError::NotEnabled => "SPI not enabled",
+            SpiError::LengthMismatch => "spi_length_mismatch",
+            SpiError::Timeout => "spi_timeout",
+        }
+    }
+}
+
 const SPI_CTRL_ENABLE: u32 = 0x0001;
 const SPI_CTRL_MODE_SHIFT: u32 = 1;
 const SPI_CTRL_MODE_MASK: u32 = 0x0006;
 
 const SPI_STATUS_TX_READY: u32 = 0x0001;
 const SPI_STATUS_RX_READY: u32 = 0x0002;
+const SPI_STATUS_BUSY: u32 = 0x0004;
 
 const SPI_POLL_LIMIT: u32 = 100_000;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    /// Peripheral that shifts back whatever was last written to TX.
+    struct EchoPeripheral {
+        shift: Cell<Option<u32>>,
+        clock: Cell<u32>,
+        hung: bool,
+        status_reads: Cell<u32>,
+    }
+
+    impl RegisterBank for EchoPeripheral {
+        fn read32(&self, offset: u64) -> u32 {
+            match offset {
+                SPI_STATUS_OFFSET => {
+                    self.status_reads.set(self.status_reads.get() + 1);
+                    if self.hung {
+                        SPI_STATUS_BUSY | SPI_STATUS_TX_READY
+                    } else if self.shift.get().is_some() {
+                        SPI_STATUS_RX_READY
+                    } else {
+                        SPI_STATUS_TX_READY
+                    }
+                }
+                SPI_RX_OFFSET => self.shift.take().unwrap_or(0),
+                _ => 0,
+            }
+        }
+
+        fn write32(&self, offset: u64, value: u32) {
+            match offset {
+                SPI_TX_OFFSET => self.shift.set(Some(value)),
+                SPI_CLK_OFFSET => self.clock.set(value),
+                _ => {}
+            }
+        }
+    }
+
+    fn spi(hung: bool) -> SPIInterface<EchoPeripheral> {
+        let spi = SPIInterface::with_registers(EchoPeripheral {
+            shift: Cell::new(None),
+            clock: Cell::new(0),
+            hung,
+            status_reads: Cell::new(0),
+        });
+        spi.enable().unwrap();
+        spi
+    }
+
+    #[test]
+    fn test_full_duplex_round_trip() {
+        let spi = spi(false);
+        spi.set_clock(20).unwrap();
+        assert_eq!(spi.regs.clock.get(), 20);
+        let tx = [0x9F, 0x00, 0xA5, 0xFF];
+        let mut rx = [0u8; 4];
+        spi.transfer_into(&tx, &mut rx).unwrap();
+        assert_eq!(rx, tx);
+        assert_eq!(spi.transfer(&tx), Ok(tx.to_vec()));
+    }
+
+    #[test]
+    fn test_busy_bus_times_out() {
+        let spi = spi(true);
+        let mut rx = [0u8; 1];
+        assert_eq!(spi.transfer_into(&[0x01], &mut rx), Err(SpiError::Timeout));
+        assert_eq!(spi.regs.status_reads.get(), SPI_POLL_LIMIT);
+        assert_eq!(spi.transfer(&[0x01]), Err(String::from("spi_timeout")));
+    }
+
+    #[test]
+    fn test_transfer_into_checks_length_then_enable() {
+        let spi = SPIInterface::with_registers(EchoPeripheral {
+            shift: Cell::new(None),
+            clock: Cell::new(0),
+            hung: false,
+            status_reads: Cell::new(0),
+        });
+        let mut rx = [0u8; 3];
+        assert_eq!(spi.transfer_into(&[0x9F, 0x00], &mut rx), Err(SpiError::LengthMismatch));
+        assert_eq!(spi.transfer_into(&[0x9F, 0x00, 0x00], &mut rx), Err(SpiError::NotEnabled));
+        assert_eq!(spi.transfer_into(&[], &mut []), Err(SpiError::NotEnabled));
+    }
+}
@@ -1,3 +1,5 @@
+use super::mmio::{MmioRegisters, RegisterBank};
+
 /// UART (Serial Communication) Driver
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -32,10 +34,61 @@ impl Default for UartConfig {
     }
 }
 
-pub struct Uart {
-    controller_index: usize,
+/// Fixed-capacity FIFO used for the RX line buffer.
+struct ByteRing {
+    buf: [u8; UART_RING_SIZE],
+    head: usize,
+    len: usize,
+}
+
+impl ByteRing {
+    const fn new() -> Self {
+        ByteRing { buf: [0; UART_RING_SIZE], head: 0, len: 0 }
+    }
+
+    fn is_full(&self) -> bool {
+        self.len == UART_RING_SIZE
+    }
+
+    fn push(&mut self, byte: u8) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        self.buf[(self.head + self.len) % UART_RING_SIZE] = byte;
+        self.len += 1;
+        true
+    }
+
+    fn pop(&mut self) -> Option<u8> {
+        if self.len == 0 {
+            return None;
+        }
+        let byte = self.buf[self.head];
+        self.head = (self.head + 1) % UART_RING_SIZE;
+        self.len -= 1;
+        Some(byte)
+    }
+
+    fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    fn position(&self, byte: u8) -> Option<usize> {
+        (0..self.len).find(|i| self.buf[(self.head + i) % UART_RING_SIZE] == byte)
+    }
+}
+
+pub struct Uart<R: RegisterBank = MmioRegisters> {
+    regs: R,
     #[allow(dead_code)]
     config: UartConfig,
+    rx_ring: ByteRing,
+    rx_dropped: u32,
+    rx_overruns: u32,
+    /// Set after an overlong line was thrown away; input is skipped up to
+    /// and including its terminating newline.
+    rx_discarding: bool,
 }
 
 impl Uart {
@@ -44,19 +97,18 @@ impl Uart {
         if controller_index >= 12 {
             return Err("invalid_uart_controller");
         }
-        Ok(Uart { controller_index, config })
-    }
-
-    fn base(&self) -> u64 {
-        crate::uart_base() + (self.controller_index as u64 * UART_CTRL_STRIDE)
+        let base = crate::uart_base() + (controller_index as u64 * UART_CTRL_STRIDE);
+        Ok(Uart::with_registers(MmioRegisters::new(base), config))
     }
+}
 
-    fn reg(&self, offset: u64) -> u64 {
-        self.base() + offset
+impl<R: RegisterBank> Uart<R> {
+    pub fn with_registers(regs: R, config: UartConfig) -> Self {
+        Uart { regs, config, rx_ring: ByteRing::new(), rx_dropped: 0, rx_overruns: 0, rx_discarding: false }
     }
 
     fn status(&self) -> u32 {
-        unsafe { core::ptr::read_volatile(self.reg(UART_STATUS_OFFSET) as *const u32) }
+        self.regs.read32(UART_STATUS_OFFSET)
     }
 
     fn wait_tx_ready(&self) -> Result<(), &'static str> {
@@ -77,24 +129,22 @@ impl Uart {
         Err("rx_timeout")
     }
 
+    pub fn enable(&self) {
+        self.regs.write32(UART_CTRL_OFFSET, UART_CTRL_ENABLE);
+    }
+
     pub fn set_baudrate(&self, _baudrate: u32) -> Result<(), &'static str> {
         if _baudrate == 0 {
             return Err("invalid_baudrate");
         }
         let divisor = (UART_REF_CLOCK_HZ / (_baudrate * 16)).max(1);
-        unsafe {
-            core::ptr::write_volatile(self.reg(UART_BAUD_OFFSET) as *mut u32, divisor);
-            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
-        }
+        self.regs.write32(UART_BAUD_OFFSET, divisor);
         Ok(())
     }
 
     pub fn write_byte(&self, _byte: u8) -> Result<(), &'static str> {
         self.wait_tx_ready()?;
-        unsafe {
-            core::ptr::write_volatile(self.reg(UART_TX_OFFSET) as *mut u32, _byte as u32);
-            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
-        }
+        self.regs.write32(UART_TX_OFFSET, _byte as u32);
         Ok(())
     }
 
@@ -108,16 +158,14 @@ impl Uart {
 
     pub fn read_byte(&self) -> Result<u8, &'static str> {
         self.wait_rx_ready()?;
-        let value = unsafe { core::ptr::read_volatile(self.reg(UART_RX_OFFSET) as *const u32) };
-        Ok(value as u8)
+        Ok(self.regs.read32(UART_RX_OFFSET) as u8)
     }
 
     pub fn try_read_byte(&self) -> Option<u8> {
         if self.status() & UART_STATUS_RX_READY == 0 {
             return None;
         }
-        let value = unsafe { core::ptr::read_volatile(self.reg(UART_RX_OFFSET) as *const u32) };
-        Some(value as u8)
+        Some(self.regs.read32(UART_RX_OFFSET) as u8)
     }
 
     /// Wait for TX to be complete
@@ -129,6 +177,81 @@ impl Uart {
         }
         return Err("tx_busy");
     }
+
+    /// Sends `line` plus a trailing newline and waits for the transmitter
+    /// to idle.
+    pub fn write_line(&self, line: &str) -> Result<(), &'static str> {
+        self.write_all(line.as_bytes())?;
+        self.write_byte(b'\n')?;
+        self.flush()
+    }
+
+    /// Moves every byte the receiver holds into the RX ring. Bytes arriving
+    /// while the ring is full are dropped and counted.
+    pub fn poll_rx(&mut self) {
+        while let Some(byte) = self.try_read_byte() {
+            if !self.rx_ring.push(byte) {
+                self.rx_dropped = self.rx_dropped.saturating_add(1);
+            }
+        }
+    }
+
+    /// Returns the next complete line (without its `\n` or `\r\n`) copied
+    /// into `buf`, or `None` while only a partial line is buffered. Bytes
+    /// beyond `buf.len()` are discarded along with the rest of the line. A
+    /// line that fills the whole RX ring without a newline is discarded
+    /// through to its eventual newline and counted as an overrun, so it
+    /// cannot wedge the ring.
+    pub fn read_line(&mut self, buf: &mut [u8]) -> Option<usize> {
+        loop {
+            self.poll_rx();
+            if self.rx_discarding {
+                let Some(end) = self.rx_ring.position(b'\n') else {
+                    self.rx_ring.clear();
+                    return None;
+                };
+                for _ in 0..=end {
+                    self.rx_ring.pop();
+                }
+                self.rx_discarding = false;
+            }
+            if let Some(end) = self.rx_ring.position(b'\n') {
+                return Some(self.take_line(end, buf));
+            }
+            if !self.rx_ring.is_full() {
+                return None;
+            }
+            self.rx_ring.clear();
+            self.rx_overruns = self.rx_overruns.saturating_add(1);
+            self.rx_discarding = true;
+        }
+    }
+
+    fn take_line(&mut self, end: usize, buf: &mut [u8]) -> usize {
+        let mut len = 0;
+        for _ in 0..end {
+            let Some(byte) = self.rx_ring.pop() else { break };
+            if len < buf.len() {
+                buf[len] = byte;
+                len += 1;
+            }
+        }
+        self.rx_ring.pop();
+        if len > 0 && len == end && buf[len - 1] == b'\r' {
+            len -= 1;
+        }
+        len
+    }
+
+    /// Number of received bytes lost to RX ring overrun.
+    pub fn rx_dropped(&self) -> u32 {
+        self.rx_dropped
+    }
+
+    /// Number of lines discarded for not fitting in the RX ring.
+    pub fn rx_overruns(&self) -> u32 {
+        self.rx_overruns
+    }
 }
 
 // Legacy interface for compatibility
@@ -144,10 +267,7 @@ impl UARTInterface {
     }
 
     pub fn enable(&self) -> Result<(), &'static str> {
-        unsafe {
-            core::ptr::write_volatile(self.uart.reg(UART_CTRL_OFFSET) as *mut u32, UART_CTRL_ENABLE);
-            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
-        }
+        self.uart.enable();
         Ok(())
     }
 
@@ -185,4 +305,98 @@ const UART_STATUS_TX_BUSY: u32 = 0x0004;
 
 const UART_REF_CLOCK_HZ: u32 = 26_000_000;
 const UART_POLL_LIMIT: u32 = 100_000;
+const UART_RING_SIZE: usize = 256;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::VecDeque;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+
+    #[derive(Default)]
+    struct MockLine {
+        rx: RefCell<VecDeque<u8>>,
+        tx: RefCell<Vec<u8>>,
+    }
+
+    impl MockLine {
+        fn feed(&self, bytes: &[u8]) {
+            self.rx.borrow_mut().extend(bytes.iter().copied());
+        }
+    }
 
+    impl RegisterBank for MockLine {
+        fn read32(&self, offset: u64) -> u32 {
+            match offset {
+                UART_STATUS_OFFSET => {
+                    let mut status = UART_STATUS_TX_READY;
+                    if !self.rx.borrow().is_empty() {
+                        status |= UART_STATUS_RX_READY;
+                    }
+                    status
+                }
+                UART_RX_OFFSET => self.rx.borrow_mut().pop_front().unwrap_or(0) as u32,
+                _ => 0,
+            }
+        }
+
+        fn write32(&self, offset: u64, value: u32) {
+            if offset == UART_TX_OFFSET {
+                self.tx.borrow_mut().push(value as u8);
+            }
+        }
+    }
+
+    fn uart() -> Uart<MockLine> {
+        Uart::with_registers(MockLine::default(), UartConfig::default())
+    }
+
+    #[test]
+    fn test_write_line_appends_newline() {
+        let uart = uart();
+        uart.write_line("AT+OK").unwrap();
+        assert_eq!(uart.regs.tx.borrow().as_slice(), b"AT+OK\n");
+    }
+
+    #[test]
+    fn test_partial_input_is_held_until_newline() {
+        let mut uart = uart();
+        let mut buf = [0u8; 32];
+        uart.regs.feed(b"STAT");
+        assert_eq!(uart.read_line(&mut buf), None);
+        uart.regs.feed(b"US\r\nNEX");
+        assert_eq!(uart.read_line(&mut buf), Some(6));
+        assert_eq!(&buf[..6], b"STATUS");
+        assert_eq!(uart.read_line(&mut buf), None);
+        uart.regs.feed(b"T\n");
+        assert_eq!(uart.read_line(&mut buf), Some(4));
+        assert_eq!(&buf[..4], b"NEXT");
+        assert_eq!(uart.rx_dropped(), 0);
+    }
+
+    #[test]
+    fn test_rx_overrun_counts_dropped_bytes() {
+        let mut uart = uart();
+        let flood = [b'x'; UART_RING_SIZE + 10];
+        uart.regs.feed(&flood);
+        uart.poll_rx();
+        assert_eq!(uart.rx_dropped(), 10);
+    }
+
+    #[test]
+    fn test_overlong_line_is_discarded_as_overrun() {
+        let mut uart = uart();
+        let mut buf = [0u8; 32];
+        uart.regs.feed(&[b'x'; UART_RING_SIZE]);
+        assert_eq!(uart.read_line(&mut buf), None);
+        assert_eq!(uart.rx_overruns(), 1);
+        uart.regs.feed(b"xx");
+        assert_eq!(uart.read_line(&mut buf), None);
+        uart.regs.feed(b"x\nOK\r\n");
+        assert_eq!(uart.read_line(&mut buf), Some(2));
+        assert_eq!(&buf[..2], b"OK");
+        assert_eq!(uart.rx_overruns(), 1);
+        assert_eq!(uart.rx_dropped(), 0);
+    }
+}
@@ -2,12 +2,14 @@ extern crate alloc;
 use alloc::string::String;
 use core::ptr::{read_volatile, write_volatile};
 use core::sync::atomic::{AtomicU32, AtomicBool, Ordering};
+use super::mmio::{MappedRegisters, RegisterBank};
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum USBSpeed {
     FullSpeed = 0,
     HighSpeed = 1,
     SuperSpeed = 2,
     SuperSpeedPlus = 3,
+    LowSpeed = 4,
 }
 impl USBSpeed {
     fn from_u32(val: u32) -> Self {
@@ -16,12 +18,23 @@ impl USBSpeed {
             1 => USBSpeed::HighSpeed,
             2 => USBSpeed::SuperSpeed,
             3 => USBSpeed::SuperSpeedPlus,
+            4 => USBSpeed::LowSpeed,
             _ => USBSpeed::HighSpeed,
         }
     }
     fn to_u32(&self) -> u32 {
         *self as u32
     }
+    /// Largest bulk packet the link allows (control packets for low speed,
+    /// which has no bulk endpoints).
+    pub fn max_packet_size(&self) -> u16 {
+        match self {
+            USBSpeed::LowSpeed => 8,
+            USBSpeed::FullSpeed => 64,
+            USBSpeed::HighSpeed => 512,
+            USBSpeed::SuperSpeed | USBSpeed::SuperSpeedPlus => 1024,
+        }
+    }
 }
 pub struct USBInterface {
     connected: AtomicBool,
@@ -99,5 +112,99 @@ impl Default for USBInterface {
     }
 }
 
+const USB_CTRL_OFFSET: u64 = 0x0;
+const USB_STATUS_OFFSET: u64 = 0x4;
+const USB_SPEED_OFFSET: u64 = 0x8;
+const USB_POWER_OFFSET: u64 = 0xC;
+
+fn usb_reg(offset: u64) -> u64 {
+    match offset {
+        USB_CTRL_OFFSET => crate::usb_ctrl(),
+        USB_STATUS_OFFSET => crate::usb_status(),
+        USB_SPEED_OFFSET => crate::usb_speed(),
+        _ => crate::usb_power(),
+    }
+}
+
+/// Typed view of the USB device controller's link state.
+pub struct UsbController<R: RegisterBank = MappedRegisters> {
+    regs: R,
+}
+
+impl UsbController {
+    pub fn new() -> Self {
+        UsbController::with_registers(MappedRegisters::new(usb_reg))
+    }
+}
+
+impl Default for UsbController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: RegisterBank> UsbController<R> {
+    pub fn with_registers(regs: R) -> Self {
+        UsbController { regs }
+    }
+
+    pub fn negotiated_speed(&self) -> USBSpeed {
+        USBSpeed::from_u32(self.regs.read32(USB_SPEED_OFFSET))
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.regs.read32(USB_STATUS_OFFSET) & USB_STATUS_PRESENT != 0
+    }
+
+    /// Enables VBUS power at the default budget, or cuts it.
+    pub fn set_power(&self, enabled: bool) {
+        let budget = if enabled { USB_DEFAULT_POWER_MA } else { 0 };
+        self.regs.write32(USB_POWER_OFFSET, budget);
+    }
+
+    pub fn is_powered(&self) -> bool {
+        self.regs.read32(USB_POWER_OFFSET) != 0
+    }
+}
+
 const USB_CTRL_ENABLE: u32 = 0x0001;
 const USB_STATUS_PRESENT: u32 = 0x0001;
+const USB_DEFAULT_POWER_MA: u32 = 500;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device_interfaces::mmio::test_support::MemoryBank;
+
+    #[test]
+    fn test_decodes_each_speed_code() {
+        let usb = UsbController::with_registers(MemoryBank::default());
+        for (code, speed) in [
+            (0, USBSpeed::FullSpeed),
+            (1, USBSpeed::HighSpeed),
+            (2, USBSpeed::SuperSpeed),
+            (3, USBSpeed::SuperSpeedPlus),
+            (4, USBSpeed::LowSpeed),
+        ] {
+            usb.regs.set(USB_SPEED_OFFSET, code);
+            assert_eq!(usb.negotiated_speed(), speed);
+        }
+        assert_eq!(USBSpeed::HighSpeed.max_packet_size(), 512);
+        assert_eq!(USBSpeed::LowSpeed.max_packet_size(), 8);
+    }
+
+    #[test]
+    fn test_connect_bit_and_power_toggle() {
+        let usb = UsbController::with_registers(MemoryBank::default());
+        assert!(!usb.is_connected());
+        usb.regs.set(USB_STATUS_OFFSET, USB_STATUS_PRESENT);
+        assert!(usb.is_connected());
+
+        usb.set_power(true);
+        assert_eq!(usb.regs.get(USB_POWER_OFFSET), USB_DEFAULT_POWER_MA);
+        assert!(usb.is_powered());
+        usb.set_power(false);
+        assert_eq!(usb.regs.get(USB_POWER_OFFSET), 0);
+        assert!(!usb.is_powered());
+    }
+}
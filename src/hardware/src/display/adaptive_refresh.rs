@@ -1,6 +1,17 @@
 use core::ptr::{read_volatile, write_volatile};
+use crate::config::{get_config, DisplayConfig};
 
 const REFRESH_BASE_OFFSET: u64 = 0x2000;
+pub const PANEL_REFRESH_RATES: [u8; 5] = [1, 30, 60, 90, 120];
+const FALLBACK_REFRESH_RATE: u8 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentHint {
+    StaticUi,
+    Scrolling,
+    Video(u8),
+    Gaming,
+}
 
 fn refresh_base() -> u64 {
     crate::display_ctrl_base() + REFRESH_BASE_OFFSET
@@ -102,3 +113,93 @@ pub fn write_data(data: u32) -> Result<(), &'static str> {
 pub fn read_data() -> u32 {
     unsafe { read_volatile(refresh_data() as *const u32) }
 }
+
+/// Panel rates the configured display can actually drive; `refresh_rate` in
+/// `DisplayConfig` is the panel maximum.
+pub fn supported_rates(display: &DisplayConfig) -> impl Iterator<Item = u8> {
+    let max = display.refresh_rate;
+    PANEL_REFRESH_RATES.into_iter().filter(move |&r| r <= max)
+}
+
+pub fn select_refresh_rate(content: ContentHint) -> u8 {
+    let display = get_config().display;
+    let mut rates = [0u8; PANEL_REFRESH_RATES.len()];
+    let mut count = 0;
+    for rate in supported_rates(&display) {
+        rates[count] = rate;
+        count += 1;
+    }
+    if count == 0 {
+        return display.refresh_rate.max(1);
+    }
+    select_refresh_rate_from(content, &rates[..count])
+}
+
+/// Static content drops to the lowest rate, scrolling and games get the
+/// highest. Video prefers the lowest rate that is a whole multiple of the
+/// source fps to avoid judder, then the lowest rate at or above it.
+pub fn select_refresh_rate_from(content: ContentHint, rates: &[u8]) -> u8 {
+    let lowest = rates.iter().copied().min();
+    let highest = rates.iter().copied().max();
+    let rate = match content {
+        ContentHint::StaticUi => lowest,
+        ContentHint::Scrolling | ContentHint::Gaming => highest,
+        ContentHint::Video(0) => lowest,
+        ContentHint::Video(fps) => rates
+            .iter()
+            .copied()
+            .filter(|&r| r >= fps && r % fps == 0)
+            .min()
+            .or_else(|| rates.iter().copied().filter(|&r| r >= fps).min())
+            .or(highest),
+    };
+    rate.unwrap_or(FALLBACK_REFRESH_RATE)
+}
+
+pub fn apply_refresh_rate(rate: u8) -> Result<(), &'static str> {
+    if rate == 0 {
+        return Err("Refresh rate must be non-zero");
+    }
+    crate::display::display_control::set_refresh(rate as u32)?;
+    crate::display::screen::set_refresh_rate(rate as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATES: [u8; 5] = [1, 30, 60, 90, 120];
+
+    #[test]
+    fn test_content_hint_mapping() {
+        assert_eq!(select_refresh_rate_from(ContentHint::StaticUi, &RATES), 1);
+        assert_eq!(select_refresh_rate_from(ContentHint::Scrolling, &RATES), 120);
+        assert_eq!(select_refresh_rate_from(ContentHint::Gaming, &RATES), 120);
+        assert_eq!(select_refresh_rate_from(ContentHint::Video(24), &RATES), 120);
+        assert_eq!(select_refresh_rate_from(ContentHint::Video(25), &RATES), 30);
+        assert_eq!(select_refresh_rate_from(ContentHint::Video(30), &RATES), 30);
+        assert_eq!(select_refresh_rate_from(ContentHint::Video(60), &RATES), 60);
+        assert_eq!(select_refresh_rate_from(ContentHint::Video(45), &RATES), 90);
+        assert_eq!(select_refresh_rate_from(ContentHint::Video(144), &RATES), 120);
+    }
+
+    #[test]
+    fn test_clamps_to_panel_maximum() {
+        let display = DisplayConfig {
+            resolution_width: 1080,
+            resolution_height: 2400,
+            refresh_rate: 90,
+            brightness_max: 100,
+        };
+        let mut rates = [0u8; 5];
+        let mut count = 0;
+        for rate in supported_rates(&display) {
+            rates[count] = rate;
+            count += 1;
+        }
+        assert_eq!(&rates[..count], &[1, 30, 60, 90]);
+        assert_eq!(select_refresh_rate_from(ContentHint::Gaming, &rates[..count]), 90);
+        assert_eq!(select_refresh_rate_from(ContentHint::Video(120), &rates[..count]), 90);
+        assert_eq!(select_refresh_rate_from(ContentHint::StaticUi, &[]), FALLBACK_REFRESH_RATE);
+    }
+}
@@ -1,6 +1,24 @@
 use core::ptr::{read_volatile, write_volatile};
 use crate::config::get_config;
 
+const PERCEPTUAL_CURVE_SCALE: u32 = 10_000;
+
+/// `(p / 100)^2.2 * 10_000` for p in 0..=100, precomputed since there is no
+/// float `powf` without std.
+const PERCEPTUAL_CURVE: [u16; 101] = [
+    0, 0, 2, 4, 8, 14, 21, 29, 39, 50,
+    63, 78, 94, 112, 132, 154, 177, 203, 230, 259,
+    290, 323, 358, 394, 433, 474, 516, 561, 608, 657,
+    707, 760, 815, 872, 932, 993, 1056, 1122, 1190, 1260,
+    1332, 1406, 1483, 1562, 1643, 1726, 1812, 1899, 1989, 2082,
+    2176, 2273, 2373, 2474, 2578, 2684, 2793, 2904, 3017, 3132,
+    3250, 3371, 3494, 3619, 3746, 3876, 4009, 4143, 4281, 4420,
+    4563, 4707, 4854, 5004, 5156, 5310, 5468, 5627, 5789, 5954,
+    6121, 6290, 6462, 6637, 6814, 6994, 7176, 7361, 7549, 7739,
+    7931, 8126, 8324, 8524, 8727, 8933, 9141, 9352, 9565, 9781,
+    10000,
+];
+
 pub fn get_max_brightness() -> u8 {
     get_config().display.brightness_max
 }
@@ -95,3 +113,59 @@ pub fn write_data(data: u32) -> Result<(), &'static str> {
 pub fn read_data() -> u32 {
     unsafe { read_volatile(crate::brightness_data() as *const u32) }
 }
+
+fn get_min() -> u32 {
+    unsafe { read_volatile(crate::brightness_min() as *const u32) }
+}
+
+fn get_max() -> u32 {
+    unsafe { read_volatile(crate::brightness_max() as *const u32) }
+}
+
+/// Maps a perceptual percentage through a 2.2 gamma curve onto the raw
+/// `min..=max` range, so equal steps in `percent` look roughly equal.
+pub fn perceptual_to_level(percent: u8, min: u32, max: u32) -> Result<u32, &'static str> {
+    if max <= min {
+        return Err("Invalid brightness range");
+    }
+    let weight = PERCEPTUAL_CURVE[percent.min(100) as usize] as u64;
+    let span = (max - min) as u64;
+    let offset = (span * weight + PERCEPTUAL_CURVE_SCALE as u64 / 2) / PERCEPTUAL_CURVE_SCALE as u64;
+    Ok(min + offset as u32)
+}
+
+pub fn set_perceptual_brightness(percent: u8) -> Result<(), &'static str> {
+    let level = perceptual_to_level(percent, get_min(), get_max())?;
+    set_level(level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoints_hit_min_and_max() {
+        assert_eq!(perceptual_to_level(0, 10, 255), Ok(10));
+        assert_eq!(perceptual_to_level(100, 10, 255), Ok(255));
+        assert_eq!(perceptual_to_level(200, 10, 255), Ok(255));
+    }
+
+    #[test]
+    fn test_midpoint_is_below_linear() {
+        let level = perceptual_to_level(50, 0, 255).unwrap();
+        assert!(level < 255 / 2);
+        assert!(level > 0);
+        let mut previous = 0;
+        for percent in 0..=100 {
+            let level = perceptual_to_level(percent, 0, 255).unwrap();
+            assert!(level >= previous);
+            previous = level;
+        }
+    }
+
+    #[test]
+    fn test_rejects_inverted_range() {
+        assert!(perceptual_to_level(50, 100, 100).is_err());
+        assert!(perceptual_to_level(50, 200, 100).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use core::ptr::{read_volatile, write_volatile};
 
 const FB_BASE_OFFSET: u64 = 0x5000;
+pub const MAX_DIRTY_RECTS: usize = 8;
 
 fn fb_base() -> u64 {
     crate::display_ctrl_base() + FB_BASE_OFFSET
@@ -98,3 +99,169 @@ pub fn write_data(data: u32) -> Result<(), &'static str> {
 pub fn read_data() -> u32 {
     unsafe { read_volatile(fb_data() as *const u32) }
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Rect {
+    pub const fn new(x: u32, y: u32, w: u32, h: u32) -> Self {
+        Rect { x, y, w, h }
+    }
+
+    fn right(&self) -> u32 {
+        self.x.saturating_add(self.w)
+    }
+
+    fn bottom(&self) -> u32 {
+        self.y.saturating_add(self.h)
+    }
+
+    /// True when the rects overlap or share an edge, i.e. merging them
+    /// never adds pixels that neither covered along the shared axis.
+    fn touches(&self, other: &Rect) -> bool {
+        self.x <= other.right() && other.x <= self.right() && self.y <= other.bottom() && other.y <= self.bottom()
+    }
+
+    fn union(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Rect::new(x, y, self.right().max(other.right()) - x, self.bottom().max(other.bottom()) - y)
+    }
+}
+
+/// Damage tracking for partial framebuffer uploads. Up to `MAX_DIRTY_RECTS`
+/// disjoint regions are kept; past that everything collapses into the
+/// bounding region.
+pub struct DirtyTracker {
+    rects: [Rect; MAX_DIRTY_RECTS],
+    len: usize,
+}
+
+impl DirtyTracker {
+    pub const fn new() -> Self {
+        DirtyTracker { rects: [Rect::new(0, 0, 0, 0); MAX_DIRTY_RECTS], len: 0 }
+    }
+
+    pub fn dirty_rects(&self) -> &[Rect] {
+        &self.rects[..self.len]
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn dirty_bounds(&self) -> Option<Rect> {
+        let mut rects = self.dirty_rects().iter();
+        let first = *rects.next()?;
+        Some(rects.fold(first, |acc, r| acc.union(r)))
+    }
+
+    pub fn mark_dirty(&mut self, x: u32, y: u32, w: u32, h: u32) {
+        if w == 0 || h == 0 {
+            return;
+        }
+        let mut merged = Rect::new(x, y, w, h);
+        // Absorbing one rect can make the result touch another, so keep
+        // folding until nothing else touches.
+        let mut i = 0;
+        while i < self.len {
+            if self.rects[i].touches(&merged) {
+                merged = merged.union(&self.rects[i]);
+                self.len -= 1;
+                self.rects[i] = self.rects[self.len];
+                i = 0;
+            } else {
+                i += 1;
+            }
+        }
+        if self.len == MAX_DIRTY_RECTS {
+            let bounds = self.dirty_bounds().map_or(merged, |b| b.union(&merged));
+            self.rects[0] = bounds;
+            self.len = 1;
+            return;
+        }
+        self.rects[self.len] = merged;
+        self.len += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Pushes each dirty region to the display data register as two words,
+    /// `x << 16 | y` then `w << 16 | h`, and clears the dirty set.
+    pub fn flush_dirty(&mut self) -> Result<usize, &'static str> {
+        self.flush_dirty_with(crate::display::display_control::write_data)
+    }
+
+    pub fn flush_dirty_with<F>(&mut self, mut write: F) -> Result<usize, &'static str>
+    where
+        F: FnMut(u32) -> Result<(), &'static str>,
+    {
+        let count = self.len;
+        for rect in self.dirty_rects() {
+            write((rect.x & 0xFFFF) << 16 | (rect.y & 0xFFFF))?;
+            write((rect.w & 0xFFFF) << 16 | (rect.h & 0xFFFF))?;
+        }
+        self.clear();
+        Ok(count)
+    }
+}
+
+impl Default for DirtyTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_rects_coalesce() {
+        let mut dirty = DirtyTracker::new();
+        dirty.mark_dirty(10, 10, 50, 50);
+        dirty.mark_dirty(40, 40, 50, 50);
+        assert_eq!(dirty.dirty_rects(), &[Rect::new(10, 10, 80, 80)]);
+        dirty.mark_dirty(90, 10, 10, 10);
+        assert_eq!(dirty.dirty_rects().len(), 1);
+        assert_eq!(dirty.dirty_bounds(), Some(Rect::new(10, 10, 90, 80)));
+    }
+
+    #[test]
+    fn test_disjoint_rects_stay_separate_up_to_cap() {
+        let mut dirty = DirtyTracker::new();
+        for i in 0..MAX_DIRTY_RECTS as u32 {
+            dirty.mark_dirty(i * 100, 0, 10, 10);
+        }
+        assert_eq!(dirty.dirty_rects().len(), MAX_DIRTY_RECTS);
+        dirty.mark_dirty(0, 500, 10, 10);
+        assert_eq!(dirty.dirty_rects(), &[Rect::new(0, 0, 710, 510)]);
+    }
+
+    #[test]
+    fn test_flush_clears_state() {
+        let mut dirty = DirtyTracker::new();
+        dirty.mark_dirty(1, 2, 3, 4);
+        dirty.mark_dirty(100, 200, 30, 40);
+        let mut words = [0u32; 4];
+        let mut n = 0;
+        let flushed = dirty
+            .flush_dirty_with(|w| {
+                words[n] = w;
+                n += 1;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(flushed, 2);
+        assert_eq!(words, [1 << 16 | 2, 3 << 16 | 4, 100 << 16 | 200, 30 << 16 | 40]);
+        assert!(dirty.is_clean());
+        assert_eq!(dirty.dirty_bounds(), None);
+    }
+}
@@ -7,3 +7,4 @@ pub mod screen;
 pub mod stylus;
 pub mod touch;
 pub use screen::{DisplayScreen, TouchScreen, TouchPoint, MAX_TOUCH_POINTS};
+pub use touch::{Gesture, GestureConfig, GestureRecognizer, SwipeDirection};
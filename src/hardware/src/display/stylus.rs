@@ -1,6 +1,10 @@
 use core::ptr::{read_volatile, write_volatile};
 
 const STYLUS_BASE_OFFSET: u64 = 0x3000;
+const STYLUS_STATUS_CONTACT: u32 = 1 << 1;
+const STYLUS_RAW_PRESSURE_MAX: u32 = 0x0FFF;
+const STYLUS_BUTTON_BARREL: u32 = 1 << 0;
+pub const STYLUS_PRESSURE_MAX: u16 = u16::MAX;
 
 fn stylus_base() -> u64 {
     crate::display_ctrl_base() + STYLUS_BASE_OFFSET
@@ -87,3 +91,67 @@ pub fn write_data(data: u32) -> Result<(), &'static str> {
 pub fn read_data() -> u32 {
     unsafe { read_volatile(stylus_data() as *const u32) }
 }
+
+/// One decoded pen report. `pressure` is normalized from the digitizer's
+/// 12-bit reading to `0..=STYLUS_PRESSURE_MAX`; tilt is in degrees from
+/// vertical, positive towards +x / +y.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StylusSample {
+    pub x: u16,
+    pub y: u16,
+    pub pressure: u16,
+    pub tilt_x: i8,
+    pub tilt_y: i8,
+    pub barrel_button: bool,
+}
+
+/// Decodes raw register values. Status bit 1 is tip contact; the buttons
+/// register carries the barrel button in bit 0 and signed tilt x/y in
+/// bits 8..16 and 16..24. Reports without tip contact yield `None`, which
+/// also drops pressure readings that arrive with no contact.
+pub fn decode_sample(status: u32, x: u32, y: u32, pressure: u32, buttons: u32) -> Option<StylusSample> {
+    if status & STYLUS_STATUS_CONTACT == 0 {
+        return None;
+    }
+    let raw = (pressure & STYLUS_RAW_PRESSURE_MAX) as u64;
+    let pressure = (raw * STYLUS_PRESSURE_MAX as u64 / STYLUS_RAW_PRESSURE_MAX as u64) as u16;
+    Some(StylusSample {
+        x: x as u16,
+        y: y as u16,
+        pressure,
+        tilt_x: (buttons >> 8) as u8 as i8,
+        tilt_y: (buttons >> 16) as u8 as i8,
+        barrel_button: buttons & STYLUS_BUTTON_BARREL != 0,
+    })
+}
+
+pub fn read_sample() -> Option<StylusSample> {
+    decode_sample(get_status(), get_x(), get_y(), get_pressure(), get_buttons())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_known_sample() {
+        let buttons = (0xF6u32 << 16) | (0x1Eu32 << 8) | STYLUS_BUTTON_BARREL;
+        let sample = decode_sample(0x3, 1200, 2400, 0x0800, buttons).unwrap();
+        assert_eq!(
+            sample,
+            StylusSample { x: 1200, y: 2400, pressure: 32775, tilt_x: 30, tilt_y: -10, barrel_button: true }
+        );
+    }
+
+    #[test]
+    fn test_pressure_range_endpoints() {
+        assert_eq!(decode_sample(0x3, 0, 0, 0, 0).unwrap().pressure, 0);
+        assert_eq!(decode_sample(0x3, 0, 0, STYLUS_RAW_PRESSURE_MAX, 0).unwrap().pressure, STYLUS_PRESSURE_MAX);
+    }
+
+    #[test]
+    fn test_no_contact_is_rejected() {
+        assert_eq!(decode_sample(0x1, 500, 500, 0x0400, 0), None);
+        assert_eq!(decode_sample(0x0, 0, 0, 0, 0), None);
+    }
+}
@@ -1,4 +1,5 @@
 use core::ptr::{read_volatile, write_volatile};
+use super::screen::{TouchPoint, MAX_TOUCH_POINTS};
 
 const TOUCH_BASE_OFFSET: u64 = 0x4000;
 
@@ -82,3 +83,359 @@ pub fn write_data(data: u32) -> Result<(), &'static str> {
     }
     Ok(())
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gesture {
+    Tap,
+    DoubleTap,
+    Swipe { dir: SwipeDirection },
+    Pinch { scale: f32 },
+    LongPress,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GestureConfig {
+    pub tap_radius: u16,
+    pub long_press_ms: u64,
+    pub swipe_min_distance: u16,
+    pub double_tap_ms: u64,
+    pub pinch_min_change_percent: u8,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        GestureConfig {
+            tap_radius: 12,
+            long_press_ms: 500,
+            swipe_min_distance: 80,
+            double_tap_ms: 300,
+            pinch_min_change_percent: 10,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Contact {
+    id: u8,
+    start_x: i32,
+    start_y: i32,
+    x: i32,
+    y: i32,
+}
+
+impl Contact {
+    fn displacement_sq(&self) -> u64 {
+        let dx = (self.x - self.start_x) as i64;
+        let dy = (self.y - self.start_y) as i64;
+        (dx * dx + dy * dy) as u64
+    }
+}
+
+fn distance(a: &Contact, b: &Contact) -> u64 {
+    let dx = (a.x - b.x) as i64;
+    let dy = (a.y - b.y) as i64;
+    isqrt((dx * dx + dy * dy) as u64)
+}
+
+fn isqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+/// Turns a stream of touch frames into gestures. State lives in fixed-size
+/// arrays so nothing allocates per frame. A gesture is reported when the
+/// last finger lifts, except `LongPress`, which fires while still held.
+/// A double tap is reported as `Tap` followed by `DoubleTap`.
+pub struct GestureRecognizer {
+    config: GestureConfig,
+    contacts: [Option<Contact>; MAX_TOUCH_POINTS],
+    started_ms: Option<u64>,
+    max_contacts: usize,
+    pinch_start: Option<u64>,
+    pinch_current: u64,
+    long_press_fired: bool,
+    last_tap: Option<(u64, i32, i32)>,
+}
+
+impl GestureRecognizer {
+    pub fn new(config: GestureConfig) -> Self {
+        GestureRecognizer {
+            config,
+            contacts: [None; MAX_TOUCH_POINTS],
+            started_ms: None,
+            max_contacts: 0,
+            pinch_start: None,
+            pinch_current: 0,
+            long_press_fired: false,
+            last_tap: None,
+        }
+    }
+
+    pub fn config(&self) -> &GestureConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: GestureConfig) {
+        self.config = config;
+    }
+
+    pub fn reset(&mut self) {
+        self.contacts = [None; MAX_TOUCH_POINTS];
+        self.started_ms = None;
+        self.max_contacts = 0;
+        self.pinch_start = None;
+        self.pinch_current = 0;
+        self.long_press_fired = false;
+    }
+
+    pub fn process_frame(&mut self, points: &[TouchPoint], now_ms: u64) -> Option<Gesture> {
+        let active = points.iter().filter(|p| p.active).count();
+        if active == 0 {
+            return self.finish(now_ms);
+        }
+        if self.started_ms.is_none() {
+            self.started_ms = Some(now_ms);
+        }
+        self.release_lifted(points);
+        for point in points.iter().filter(|p| p.active) {
+            self.track(point);
+        }
+        let tracked = self.contacts.iter().flatten().count();
+        self.max_contacts = self.max_contacts.max(tracked);
+
+        if tracked >= 2 {
+            let mut pair = self.contacts.iter().flatten();
+            if let (Some(a), Some(b)) = (pair.next(), pair.next()) {
+                let d = distance(a, b);
+                self.pinch_start.get_or_insert(d);
+                self.pinch_current = d;
+            }
+            return None;
+        }
+
+        let started = self.started_ms.unwrap_or(now_ms);
+        if self.max_contacts == 1
+            && !self.long_press_fired
+            && now_ms.saturating_sub(started) >= self.config.long_press_ms
+            && self.within_tap_radius()
+        {
+            self.long_press_fired = true;
+            return Some(Gesture::LongPress);
+        }
+        None
+    }
+
+    /// Frees the slot of every contact that no longer appears as active, so a
+    /// lifted finger cannot pin the pinch pair or block a new touch id. The
+    /// pinch baseline restarts because the measured pair has changed.
+    fn release_lifted(&mut self, points: &[TouchPoint]) {
+        for slot in self.contacts.iter_mut() {
+            if slot.is_some_and(|c| !points.iter().any(|p| p.active && p.id == c.id)) {
+                *slot = None;
+                self.pinch_start = None;
+            }
+        }
+    }
+
+    fn track(&mut self, point: &TouchPoint) {
+        let (x, y) = (point.x as i32, point.y as i32);
+        if let Some(c) = self.contacts.iter_mut().flatten().find(|c| c.id == point.id) {
+            c.x = x;
+            c.y = y;
+            return;
+        }
+        if let Some(slot) = self.contacts.iter_mut().find(|c| c.is_none()) {
+            *slot = Some(Contact { id: point.id, start_x: x, start_y: y, x, y });
+        }
+    }
+
+    fn primary(&self) -> Option<Contact> {
+        self.contacts.iter().flatten().next().copied()
+    }
+
+    fn within_tap_radius(&self) -> bool {
+        let radius = self.config.tap_radius as u64;
+        self.primary().is_some_and(|c| c.displacement_sq() <= radius * radius)
+    }
+
+    fn finish(&mut self, now_ms: u64) -> Option<Gesture> {
+        let started = self.started_ms?;
+        let gesture = if self.long_press_fired {
+            None
+        } else if self.max_contacts >= 2 {
+            self.classify_pinch()
+        } else {
+            self.classify_single(started, now_ms)
+        };
+        self.reset();
+        gesture
+    }
+
+    fn classify_pinch(&self) -> Option<Gesture> {
+        let start = self.pinch_start.filter(|&d| d > 0)?;
+        let change = self.pinch_current.abs_diff(start) * 100;
+        if change < start * self.config.pinch_min_change_percent as u64 {
+            return None;
+        }
+        Some(Gesture::Pinch { scale: self.pinch_current as f32 / start as f32 })
+    }
+
+    fn classify_single(&mut self, started: u64, now_ms: u64) -> Option<Gesture> {
+        let contact = self.primary()?;
+        if self.within_tap_radius() {
+            if now_ms.saturating_sub(started) >= self.config.long_press_ms {
+                return Some(Gesture::LongPress);
+            }
+            return Some(self.register_tap(contact, now_ms));
+        }
+        let swipe = self.config.swipe_min_distance as u64;
+        if contact.displacement_sq() < swipe * swipe {
+            return None;
+        }
+        let dx = contact.x - contact.start_x;
+        let dy = contact.y - contact.start_y;
+        let dir = if dx.abs() >= dy.abs() {
+            if dx > 0 { SwipeDirection::Right } else { SwipeDirection::Left }
+        } else if dy > 0 {
+            SwipeDirection::Down
+        } else {
+            SwipeDirection::Up
+        };
+        Some(Gesture::Swipe { dir })
+    }
+
+    fn register_tap(&mut self, contact: Contact, now_ms: u64) -> Gesture {
+        let radius = self.config.tap_radius as i64;
+        if let Some((at, x, y)) = self.last_tap.take() {
+            let dx = (contact.start_x - x) as i64;
+            let dy = (contact.start_y - y) as i64;
+            if now_ms.saturating_sub(at) <= self.config.double_tap_ms && dx * dx + dy * dy <= radius * radius {
+                return Gesture::DoubleTap;
+            }
+        }
+        self.last_tap = Some((now_ms, contact.start_x, contact.start_y));
+        Gesture::Tap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(id: u8, x: u16, y: u16) -> TouchPoint {
+        TouchPoint { x, y, pressure: 40, id, active: true }
+    }
+
+    fn feed(rec: &mut GestureRecognizer, frames: &[(&[TouchPoint], u64)]) -> Option<Gesture> {
+        let mut last = None;
+        for (points, t) in frames {
+            if let Some(g) = rec.process_frame(points, *t) {
+                last = Some(g);
+            }
+        }
+        last
+    }
+
+    #[test]
+    fn test_tap_and_jitter() {
+        let mut rec = GestureRecognizer::new(GestureConfig::default());
+        let g = feed(&mut rec, &[(&[point(0, 100, 100)], 0), (&[point(0, 105, 96)], 40), (&[point(0, 108, 103)], 80), (&[], 120)]);
+        assert_eq!(g, Some(Gesture::Tap));
+    }
+
+    #[test]
+    fn test_double_tap() {
+        let mut rec = GestureRecognizer::new(GestureConfig::default());
+        assert_eq!(feed(&mut rec, &[(&[point(0, 200, 200)], 0), (&[], 60)]), Some(Gesture::Tap));
+        assert_eq!(feed(&mut rec, &[(&[point(0, 203, 198)], 200), (&[], 260)]), Some(Gesture::DoubleTap));
+        assert_eq!(feed(&mut rec, &[(&[point(0, 200, 200)], 1000), (&[], 1060)]), Some(Gesture::Tap));
+    }
+
+    #[test]
+    fn test_swipe_directions() {
+        let mut rec = GestureRecognizer::new(GestureConfig::default());
+        let right = feed(&mut rec, &[(&[point(0, 100, 500)], 0), (&[point(0, 250, 510)], 50), (&[], 90)]);
+        assert_eq!(right, Some(Gesture::Swipe { dir: SwipeDirection::Right }));
+        let up = feed(&mut rec, &[(&[point(0, 300, 900)], 500), (&[point(0, 310, 600)], 560), (&[], 600)]);
+        assert_eq!(up, Some(Gesture::Swipe { dir: SwipeDirection::Up }));
+        let short = feed(&mut rec, &[(&[point(0, 300, 300)], 1000), (&[point(0, 340, 300)], 1050), (&[], 1090)]);
+        assert_eq!(short, None);
+    }
+
+    #[test]
+    fn test_pinch() {
+        let mut rec = GestureRecognizer::new(GestureConfig::default());
+        let g = feed(
+            &mut rec,
+            &[
+                (&[point(0, 400, 400), point(1, 500, 400)], 0),
+                (&[point(0, 350, 400), point(1, 550, 400)], 50),
+                (&[], 100),
+            ],
+        );
+        match g {
+            Some(Gesture::Pinch { scale }) => assert!((scale - 2.0).abs() < 0.01),
+            other => panic!("expected pinch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_lifted_contact_is_released() {
+        let mut rec = GestureRecognizer::new(GestureConfig::default());
+        let g = feed(
+            &mut rec,
+            &[
+                (&[point(0, 400, 400), point(1, 500, 400)], 0),
+                (&[point(1, 500, 400)], 40),
+                (&[point(1, 500, 400), point(2, 300, 400)], 80),
+                (&[point(1, 500, 400), point(2, 100, 400)], 120),
+                (&[], 160),
+            ],
+        );
+        match g {
+            Some(Gesture::Pinch { scale }) => assert!((scale - 2.0).abs() < 0.01),
+            other => panic!("expected pinch, got {:?}", other),
+        }
+        assert_eq!(rec.contacts.iter().flatten().count(), 0);
+    }
+
+    #[test]
+    fn test_coincident_pinch_start_is_ignored() {
+        let mut rec = GestureRecognizer::new(GestureConfig::default());
+        let g = feed(
+            &mut rec,
+            &[
+                (&[point(0, 400, 400), point(1, 400, 400)], 0),
+                (&[point(0, 300, 400), point(1, 500, 400)], 50),
+                (&[], 100),
+            ],
+        );
+        assert_eq!(g, None);
+    }
+
+    #[test]
+    fn test_long_press() {
+        let mut rec = GestureRecognizer::new(GestureConfig::default());
+        assert_eq!(rec.process_frame(&[point(0, 50, 50)], 0), None);
+        assert_eq!(rec.process_frame(&[point(0, 52, 51)], 300), None);
+        assert_eq!(rec.process_frame(&[point(0, 53, 50)], 520), Some(Gesture::LongPress));
+        assert_eq!(rec.process_frame(&[point(0, 53, 50)], 700), None);
+        assert_eq!(rec.process_frame(&[], 800), None);
+    }
+}
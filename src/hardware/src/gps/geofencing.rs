@@ -1,3 +1,7 @@
+extern crate alloc;
+use alloc::vec::Vec;
+use super::gps::GnssFix;
+
 pub fn set_geofence(lat: u32, lon: u32, radius: u32) -> Result<(), &'static str> {
     unsafe {
         core::ptr::write_volatile(crate::geo_ctrl() as *mut u32, 0x1);
@@ -27,3 +31,195 @@ pub fn clear_geofence() -> Result<(), &'static str> {
     }
     Ok(())
 }
+
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+/// Distance either side of a fence boundary inside which the monitor keeps
+/// its previous state, so GNSS jitter at the edge cannot flap events.
+pub const GEOFENCE_HYSTERESIS_M: f64 = 25.0;
+pub const GEOFENCE_MAX: usize = 16;
+
+/// Great-circle distance in metres between two coordinates in degrees.
+pub fn haversine_m(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = libm::sin(d_lat / 2.0) * libm::sin(d_lat / 2.0)
+        + libm::cos(lat1.to_radians()) * libm::cos(lat2.to_radians()) * libm::sin(d_lon / 2.0) * libm::sin(d_lon / 2.0);
+    2.0 * EARTH_RADIUS_M * libm::asin(libm::sqrt(a.min(1.0)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geofence {
+    pub center_lat: f64,
+    pub center_lon: f64,
+    pub radius_m: f64,
+}
+
+impl Geofence {
+    pub fn distance_m(&self, fix: &GnssFix) -> f64 {
+        haversine_m(self.center_lat, self.center_lon, fix.latitude, fix.longitude)
+    }
+}
+
+/// Boundary crossing for the fence at the given index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoEvent {
+    Entered(usize),
+    Exited(usize),
+}
+
+/// Tracks which registered fences the device is inside. A fence is entered
+/// once the fix is `GEOFENCE_HYSTERESIS_M` inside its radius and exited once
+/// it is that far outside; positions in between keep the current state.
+pub struct GeofenceMonitor {
+    fences: Vec<(Geofence, bool)>,
+}
+
+impl GeofenceMonitor {
+    pub fn new() -> Self {
+        GeofenceMonitor { fences: Vec::new() }
+    }
+
+    pub fn add_fence(&mut self, fence: Geofence) -> Result<usize, &'static str> {
+        if self.fences.len() >= GEOFENCE_MAX {
+            return Err("geofence_limit");
+        }
+        // The fence must be wider than the hysteresis band, otherwise the
+        // entry threshold `radius_m - GEOFENCE_HYSTERESIS_M` is unreachable.
+        if !fence.radius_m.is_finite() || fence.radius_m <= GEOFENCE_HYSTERESIS_M {
+            return Err("invalid_radius");
+        }
+        self.fences.push((fence, false));
+        Ok(self.fences.len() - 1)
+    }
+
+    pub fn is_inside(&self, index: usize) -> bool {
+        self.fences.get(index).is_some_and(|(_, inside)| *inside)
+    }
+
+    /// Applies the fix to every registered fence and yields each crossing it
+    /// causes, in fence order. State is committed for all fences before the
+    /// events are returned.
+    pub fn update(&mut self, fix: &GnssFix) -> impl Iterator<Item = GeoEvent> {
+        let mut events = [None; GEOFENCE_MAX];
+        for (index, (fence, inside)) in self.fences.iter_mut().enumerate() {
+            let distance = fence.distance_m(fix);
+            if !*inside && distance <= fence.radius_m - GEOFENCE_HYSTERESIS_M {
+                *inside = true;
+                events[index] = Some(GeoEvent::Entered(index));
+            } else if *inside && distance > fence.radius_m + GEOFENCE_HYSTERESIS_M {
+                *inside = false;
+                events[index] = Some(GeoEvent::Exited(index));
+            }
+        }
+        events.into_iter().flatten()
+    }
+}
+
+impl Default for GeofenceMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::gps::FixQuality;
+
+    const CENTER_LAT: f64 = 48.8584;
+    const CENTER_LON: f64 = 2.2945;
+    /// Degrees of latitude per metre.
+    const LAT_PER_M: f64 = 1.0 / 111_195.0;
+
+    fn fix_at_offset_m(north_m: f64) -> GnssFix {
+        GnssFix {
+            latitude: CENTER_LAT + north_m * LAT_PER_M,
+            longitude: CENTER_LON,
+            altitude_m: 0.0,
+            quality: FixQuality::ThreeD,
+            timestamp_ms: 0,
+        }
+    }
+
+    fn fence() -> Geofence {
+        Geofence { center_lat: CENTER_LAT, center_lon: CENTER_LON, radius_m: 200.0 }
+    }
+
+    #[test]
+    fn test_haversine_known_distance() {
+        let d = haversine_m(CENTER_LAT, CENTER_LON, CENTER_LAT + 1000.0 * LAT_PER_M, CENTER_LON);
+        assert!((d - 1000.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_track_crossing_fence_emits_one_enter_and_one_exit() {
+        let mut monitor = GeofenceMonitor::new();
+        let id = monitor.add_fence(fence()).unwrap();
+        // Approach from 1km north, jitter around the boundary on the way in
+        // and out, then leave to the south.
+        let track = [
+            1000.0, 600.0, 300.0, 210.0, 190.0, 205.0, 195.0, 150.0, 50.0, 0.0, -100.0, -190.0, -210.0,
+            -195.0, -215.0, -205.0, -240.0, -400.0, -800.0,
+        ];
+        let mut entered = 0;
+        let mut exited = 0;
+        for north in track {
+            for event in monitor.update(&fix_at_offset_m(north)) {
+                match event {
+                    GeoEvent::Entered(i) => {
+                        assert_eq!(i, id);
+                        assert_eq!(exited, 0);
+                        entered += 1;
+                    }
+                    GeoEvent::Exited(i) => {
+                        assert_eq!(i, id);
+                        exited += 1;
+                    }
+                }
+            }
+        }
+        assert_eq!((entered, exited), (1, 1));
+        assert!(!monitor.is_inside(id));
+    }
+
+    #[test]
+    fn test_edge_jitter_alone_never_fires() {
+        let mut monitor = GeofenceMonitor::new();
+        monitor.add_fence(fence()).unwrap();
+        for north in [230.0, 190.0, 210.0, 180.0, 220.0, 200.0, 185.0] {
+            assert_eq!(monitor.update(&fix_at_offset_m(north)).next(), None);
+        }
+    }
+
+    #[test]
+    fn test_multiple_fences_tracked_independently() {
+        let mut monitor = GeofenceMonitor::new();
+        let outer = monitor.add_fence(Geofence { radius_m: 1000.0, ..fence() }).unwrap();
+        let inner = monitor.add_fence(fence()).unwrap();
+        let events = |monitor: &mut GeofenceMonitor, north: f64| monitor.update(&fix_at_offset_m(north)).collect::<Vec<_>>();
+        assert_eq!(events(&mut monitor, 500.0), [GeoEvent::Entered(outer)]);
+        assert_eq!(events(&mut monitor, 500.0), []);
+        assert_eq!(events(&mut monitor, 0.0), [GeoEvent::Entered(inner)]);
+        assert_eq!(events(&mut monitor, 2000.0), [GeoEvent::Exited(outer), GeoEvent::Exited(inner)]);
+        assert_eq!(events(&mut monitor, 2000.0), []);
+    }
+
+    #[test]
+    fn test_simultaneous_crossings_are_all_reported() {
+        let mut monitor = GeofenceMonitor::new();
+        let a = monitor.add_fence(fence()).unwrap();
+        let b = monitor.add_fence(Geofence { radius_m: 300.0, ..fence() }).unwrap();
+        let entered: Vec<_> = monitor.update(&fix_at_offset_m(0.0)).collect();
+        assert_eq!(entered, [GeoEvent::Entered(a), GeoEvent::Entered(b)]);
+        let exited: Vec<_> = monitor.update(&fix_at_offset_m(1000.0)).collect();
+        assert_eq!(exited, [GeoEvent::Exited(a), GeoEvent::Exited(b)]);
+    }
+
+    #[test]
+    fn test_radius_within_hysteresis_is_rejected() {
+        let mut monitor = GeofenceMonitor::new();
+        assert_eq!(monitor.add_fence(Geofence { radius_m: GEOFENCE_HYSTERESIS_M, ..fence() }), Err("invalid_radius"));
+        assert!(monitor.add_fence(Geofence { radius_m: GEOFENCE_HYSTERESIS_M + 1.0, ..fence() }).is_ok());
+        assert_eq!(monitor.add_fence(Geofence { radius_m: 0.0, ..fence() }), Err("invalid_radius"));
+    }
+}
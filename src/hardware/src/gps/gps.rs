@@ -1,3 +1,5 @@
+use crate::device_interfaces::mmio::{MappedRegisters, RegisterBank};
+
 pub fn enable() -> Result<(), &'static str> {
     unsafe {
         core::ptr::write_volatile(crate::gnss_ctrl() as *mut u32, 0x1);
@@ -27,3 +29,163 @@ pub fn disable() -> Result<(), &'static str> {
     }
     Ok(())
 }
+
+const GNSS_STATUS_QUALITY_MASK: u32 = 0x3;
+const GNSS_COORD_SCALE: f64 = 1e7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixQuality {
+    TwoD,
+    ThreeD,
+    Differential,
+}
+
+/// Position solution. Coordinates are degrees, altitude metres above the
+/// ellipsoid; `timestamp_ms` is the caller's clock when the fix was read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GnssFix {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_m: f32,
+    pub quality: FixQuality,
+    pub timestamp_ms: u64,
+}
+
+impl GnssFix {
+    pub fn age_ms(&self, now_ms: u64) -> u64 {
+        now_ms.saturating_sub(self.timestamp_ms)
+    }
+}
+
+const GNSS_STATUS_OFFSET: u64 = 0x0;
+const GNSS_LAT_OFFSET: u64 = 0x4;
+const GNSS_LON_OFFSET: u64 = 0x8;
+const GNSS_ALT_OFFSET: u64 = 0xC;
+
+/// Maps the receiver's solution registers, which sit at unrelated
+/// addresses in the board map, onto contiguous driver offsets.
+fn gnss_reg(offset: u64) -> u64 {
+    match offset {
+        GNSS_STATUS_OFFSET => crate::gnss_status(),
+        GNSS_LAT_OFFSET => crate::gnss_lat(),
+        GNSS_LON_OFFSET => crate::gnss_lon(),
+        _ => crate::gnss_alt(),
+    }
+}
+
+/// Decodes the receiver's latched solution. Latitude and longitude are
+/// signed 1e-7 degree fixed point, altitude signed millimetres; the low two
+/// status bits give the fix quality with 0 meaning no fix.
+pub struct GnssReceiver<R: RegisterBank = MappedRegisters> {
+    regs: R,
+    last_fix: Option<GnssFix>,
+}
+
+impl GnssReceiver {
+    pub fn new() -> Self {
+        GnssReceiver::with_registers(MappedRegisters::new(gnss_reg))
+    }
+}
+
+impl Default for GnssReceiver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: RegisterBank> GnssReceiver<R> {
+    pub fn with_registers(regs: R) -> Self {
+        GnssReceiver { regs, last_fix: None }
+    }
+
+    /// Returns the current fix, or `None` when the receiver has no fix or
+    /// reports coordinates outside the valid range.
+    pub fn read_fix(&mut self, now_ms: u64) -> Option<GnssFix> {
+        let quality = match self.regs.read32(GNSS_STATUS_OFFSET) & GNSS_STATUS_QUALITY_MASK {
+            1 => FixQuality::TwoD,
+            2 => FixQuality::ThreeD,
+            3 => FixQuality::Differential,
+            _ => return None,
+        };
+        let latitude = self.regs.read32(GNSS_LAT_OFFSET) as i32 as f64 / GNSS_COORD_SCALE;
+        let longitude = self.regs.read32(GNSS_LON_OFFSET) as i32 as f64 / GNSS_COORD_SCALE;
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return None;
+        }
+        let altitude_m = self.regs.read32(GNSS_ALT_OFFSET) as i32 as f32 / 1000.0;
+        let fix = GnssFix { latitude, longitude, altitude_m, quality, timestamp_ms: now_ms };
+        self.last_fix = Some(fix);
+        Some(fix)
+    }
+
+    pub fn last_fix(&self) -> Option<GnssFix> {
+        self.last_fix
+    }
+
+    /// True when no fix has been read yet or the last one is older than
+    /// `max_age_ms`.
+    pub fn is_fix_stale(&self, now_ms: u64, max_age_ms: u64) -> bool {
+        self.last_fix.is_none_or(|fix| fix.age_ms(now_ms) > max_age_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device_interfaces::mmio::test_support::MemoryBank;
+
+    fn receiver(status: u32, lat: i32, lon: i32) -> GnssReceiver<MemoryBank> {
+        let regs = MemoryBank::default();
+        regs.set(GNSS_STATUS_OFFSET, status);
+        regs.set(GNSS_LAT_OFFSET, lat as u32);
+        regs.set(GNSS_LON_OFFSET, lon as u32);
+        regs.set(GNSS_ALT_OFFSET, -12_500i32 as u32);
+        GnssReceiver::with_registers(regs)
+    }
+
+    #[test]
+    fn test_decodes_valid_fix() {
+        let mut gnss = receiver(2, 488_566_140, 23_522_190);
+        let fix = gnss.read_fix(1_000).unwrap();
+        assert!((fix.latitude - 48.856614).abs() < 1e-9);
+        assert!((fix.longitude - 2.352219).abs() < 1e-9);
+        assert_eq!(fix.altitude_m, -12.5);
+        assert_eq!(fix.quality, FixQuality::ThreeD);
+        assert_eq!(fix.timestamp_ms, 1_000);
+
+        gnss.regs.set(GNSS_LAT_OFFSET, -338_688_000i32 as u32);
+        gnss.regs.set(GNSS_LON_OFFSET, 1_512_093_000);
+        let fix = gnss.read_fix(2_000).unwrap();
+        assert!(fix.latitude < 0.0 && fix.longitude > 151.0);
+    }
+
+    #[test]
+    fn test_no_fix_status_returns_none() {
+        let mut gnss = receiver(0, 488_566_140, 23_522_190);
+        assert_eq!(gnss.read_fix(1_000), None);
+        assert_eq!(gnss.last_fix(), None);
+    }
+
+    #[test]
+    fn test_out_of_range_coordinate_is_rejected() {
+        let mut gnss = receiver(2, 950_000_000, 0);
+        assert_eq!(gnss.read_fix(1_000), None);
+        gnss.regs.set(GNSS_LAT_OFFSET, 0);
+        gnss.regs.set(GNSS_LON_OFFSET, -1_850_000_000i32 as u32);
+        assert_eq!(gnss.read_fix(1_000), None);
+    }
+
+    #[test]
+    fn test_staleness_against_simulated_time() {
+        let mut gnss = receiver(1, 100_000_000, 100_000_000);
+        assert!(gnss.is_fix_stale(0, 5_000));
+        gnss.read_fix(10_000).unwrap();
+        assert!(!gnss.is_fix_stale(12_000, 5_000));
+        assert!(!gnss.is_fix_stale(15_000, 5_000));
+        assert!(gnss.is_fix_stale(15_001, 5_000));
+
+        gnss.regs.set(GNSS_STATUS_OFFSET, 0);
+        assert_eq!(gnss.read_fix(20_000), None);
+        assert!(gnss.is_fix_stale(20_000, 5_000));
+    }
+}
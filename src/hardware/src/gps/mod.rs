@@ -1,5 +1,6 @@
 pub mod gps;
 pub mod geofencing;
 pub mod location;
-pub use gps::GPS;
+pub use gps::{GPS, GnssFix, GnssReceiver, FixQuality};
 pub use location::Location;
+pub use geofencing::{Geofence, GeofenceMonitor, GeoEvent};
@@ -10,3 +10,4 @@ pub mod screen;
 pub mod thermal_scaling;
 pub use gpu_control::GPUControl;
 pub use mali::Mali;
+pub use thermal_scaling::ThermalGovernor;
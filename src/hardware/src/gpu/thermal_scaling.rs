@@ -1,6 +1,7 @@
 extern crate alloc;
 use alloc::vec::Vec;
 use core::sync::atomic::{AtomicU32, Ordering};
+use crate::device_interfaces::mmio::{MmioRegisters, RegisterBank};
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum GpuFrequencyLevel {
     Minimum = 10,
@@ -131,6 +132,54 @@ impl GpuFrequencyScaler {
         (25, false)
     }
 }
+/// The frequency register is a block of its own; the governor writes it
+/// at offset 0.
+const GPU_FREQUENCY_OFFSET: u64 = 0;
+/// Share of the maximum GPU frequency allowed at each throttle level.
+pub const THERMAL_THROTTLE_STEPS_PERCENT: [u32; 4] = [100, 80, 60, 40];
+/// Steps the GPU frequency down one level per update while the temperature
+/// is at or above `throttle_temp`, and back up one level per update once it
+/// drops below `warning_temp`. Between the two it holds, so readings hovering
+/// around one threshold don't make the clock flap.
+pub struct ThermalGovernor<R: RegisterBank = MmioRegisters> {
+    regs: R,
+    thermal: crate::config::ThermalConfig,
+    max_frequency_mhz: u32,
+    level: usize,
+}
+impl ThermalGovernor {
+    pub fn new(thermal: crate::config::ThermalConfig, max_frequency_mhz: u32) -> Self {
+        ThermalGovernor::with_registers(MmioRegisters::new(crate::gpu_frequency_reg()), thermal, max_frequency_mhz)
+    }
+    pub fn from_config(config: &crate::config::HardwareConfig) -> Self {
+        ThermalGovernor::new(config.thermal, config.gpu.max_frequency)
+    }
+}
+impl<R: RegisterBank> ThermalGovernor<R> {
+    pub fn with_registers(regs: R, thermal: crate::config::ThermalConfig, max_frequency_mhz: u32) -> Self {
+        ThermalGovernor { regs, thermal, max_frequency_mhz, level: 0 }
+    }
+    pub fn throttle_level(&self) -> u8 {
+        self.level as u8
+    }
+    pub fn current_frequency_mhz(&self) -> u32 {
+        self.max_frequency_mhz * THERMAL_THROTTLE_STEPS_PERCENT[self.level] / 100
+    }
+    /// Feeds a temperature reading and returns the GPU frequency now in force.
+    pub fn update(&mut self, temp_celsius: i8) -> u32 {
+        let previous = self.level;
+        if temp_celsius >= self.thermal.throttle_temp {
+            self.level = (self.level + 1).min(THERMAL_THROTTLE_STEPS_PERCENT.len() - 1);
+        } else if temp_celsius < self.thermal.warning_temp {
+            self.level = self.level.saturating_sub(1);
+        }
+        let frequency = self.current_frequency_mhz();
+        if self.level != previous {
+            self.regs.write32(GPU_FREQUENCY_OFFSET, frequency);
+        }
+        frequency
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,6 +235,51 @@ mod tests {
         let freq = scaler.get_current_frequency_mhz();
         assert!(freq > 300 && freq < 900);
     }
+    #[derive(Default)]
+    struct FrequencyLog {
+        writes: core::cell::RefCell<Vec<u32>>,
+    }
+    impl RegisterBank for FrequencyLog {
+        fn read32(&self, _offset: u64) -> u32 {
+            self.writes.borrow().last().copied().unwrap_or(0)
+        }
+        fn write32(&self, offset: u64, value: u32) {
+            assert_eq!(offset, GPU_FREQUENCY_OFFSET);
+            self.writes.borrow_mut().push(value);
+        }
+    }
+    fn governor() -> ThermalGovernor<FrequencyLog> {
+        let thermal = crate::config::ThermalConfig { critical_temp: 95, throttle_temp: 85, warning_temp: 75 };
+        ThermalGovernor::with_registers(FrequencyLog::default(), thermal, 1000)
+    }
+    #[test]
+    fn test_governor_steps_down_past_throttle_temp() {
+        let mut gov = governor();
+        for temp in [60, 70, 80, 84] {
+            assert_eq!(gov.update(temp), 1000);
+        }
+        assert!(gov.regs.writes.borrow().is_empty());
+        assert_eq!(gov.update(85), 800);
+        assert_eq!(gov.update(88), 600);
+        assert_eq!(gov.update(90), 400);
+        assert_eq!(gov.update(93), 400);
+        assert_eq!(gov.throttle_level(), 3);
+        assert_eq!(*gov.regs.writes.borrow(), [800, 600, 400]);
+    }
+    #[test]
+    fn test_governor_recovers_below_warning_temp_with_hysteresis() {
+        let mut gov = governor();
+        gov.update(86);
+        gov.update(86);
+        assert_eq!(gov.throttle_level(), 2);
+        assert_eq!(gov.update(80), 600);
+        assert_eq!(gov.update(75), 600);
+        assert_eq!(gov.update(74), 800);
+        assert_eq!(gov.update(70), 1000);
+        assert_eq!(gov.update(60), 1000);
+        assert_eq!(gov.throttle_level(), 0);
+        assert_eq!(*gov.regs.writes.borrow(), [800, 600, 800, 1000]);
+    }
     #[test]
     fn test_power_state_management() {
         let scaler = GpuFrequencyScaler::new(1000);
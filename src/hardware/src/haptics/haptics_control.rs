@@ -1,5 +1,7 @@
 extern crate alloc;
+use alloc::collections::VecDeque;
 use alloc::string::String;
+use super::linear_actuator::LinearActuator;
 
 pub struct Vibrator {
     #[allow(dead_code)]
@@ -12,14 +14,42 @@ impl Vibrator {
     }
 }
 
-pub struct LinearActuator {
-    #[allow(dead_code)]
-    frequency: u32,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HapticFrame {
+    pub amplitude: u8,
+    pub duration_ms: u16,
 }
 
-impl LinearActuator {
-    pub fn new() -> Self {
-        LinearActuator { frequency: 200 }
+impl HapticFrame {
+    pub const fn new(amplitude: u8, duration_ms: u16) -> Self {
+        HapticFrame { amplitude, duration_ms }
+    }
+}
+
+const CLICK_FRAMES: [HapticFrame; 1] = [HapticFrame::new(255, 10)];
+const DOUBLE_CLICK_FRAMES: [HapticFrame; 3] =
+    [HapticFrame::new(255, 10), HapticFrame::new(0, 60), HapticFrame::new(255, 10)];
+const TICK_FRAMES: [HapticFrame; 1] = [HapticFrame::new(120, 5)];
+const HEAVY_FRAMES: [HapticFrame; 1] = [HapticFrame::new(255, 30)];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HapticEffect<'a> {
+    Click,
+    DoubleClick,
+    Tick,
+    Heavy,
+    Custom(&'a [HapticFrame]),
+}
+
+impl<'a> HapticEffect<'a> {
+    pub fn frames(&self) -> &'a [HapticFrame] {
+        match *self {
+            HapticEffect::Click => &CLICK_FRAMES,
+            HapticEffect::DoubleClick => &DOUBLE_CLICK_FRAMES,
+            HapticEffect::Tick => &TICK_FRAMES,
+            HapticEffect::Heavy => &HEAVY_FRAMES,
+            HapticEffect::Custom(frames) => frames,
+        }
     }
 }
 
@@ -28,6 +58,9 @@ pub struct HapticsController {
     linear: LinearActuator,
     #[allow(dead_code)]
     enabled: bool,
+    queue: VecDeque<HapticFrame>,
+    current: Option<HapticFrame>,
+    frame_ends_ms: u64,
 }
 impl HapticsController {
     pub fn new() -> Self {
@@ -35,6 +68,9 @@ impl HapticsController {
             vibrator: Vibrator::new(),
             linear: LinearActuator::new(),
             enabled: false,
+            queue: VecDeque::new(),
+            current: None,
+            frame_ends_ms: 0,
         }
     }
     pub fn click_feedback(&self) -> Result<(), String> {
@@ -52,6 +88,50 @@ impl HapticsController {
     pub fn get_linear(&self) -> &LinearActuator {
         &self.linear
     }
+    /// Replaces any effect in progress and starts the first frame at
+    /// `now_ms`; call `tick` as time advances to step through the rest.
+    pub fn play(&mut self, effect: HapticEffect, now_ms: u64) -> Result<(), String> {
+        self.queue.clear();
+        self.queue.extend(effect.frames().iter().copied());
+        self.start_next(now_ms)
+    }
+    /// Advances playback to `now_ms`, moving past every frame whose duration
+    /// has elapsed. Frame boundaries are taken from the schedule rather than
+    /// `now_ms`, so late ticks do not stretch the effect.
+    pub fn tick(&mut self, now_ms: u64) -> Result<(), String> {
+        while self.current.is_some() && now_ms >= self.frame_ends_ms {
+            self.start_next(self.frame_ends_ms)?;
+        }
+        Ok(())
+    }
+    pub fn stop(&mut self) -> Result<(), String> {
+        self.queue.clear();
+        self.current = None;
+        self.linear.set_amplitude(0.0)
+    }
+    pub fn is_playing(&self) -> bool {
+        self.current.is_some()
+    }
+    pub fn current_frame(&self) -> Option<HapticFrame> {
+        self.current
+    }
+    pub fn queued_frames(&self) -> usize {
+        self.queue.len()
+    }
+    fn start_next(&mut self, start_ms: u64) -> Result<(), String> {
+        match self.queue.pop_front() {
+            Some(frame) => {
+                self.linear.set_amplitude(frame.amplitude as f32 / 255.0)?;
+                self.current = Some(frame);
+                self.frame_ends_ms = start_ms + frame.duration_ms as u64;
+            }
+            None => {
+                self.current = None;
+                self.linear.set_amplitude(0.0)?;
+            }
+        }
+        Ok(())
+    }
 }
 impl Default for HapticsController {
     fn default() -> Self {
@@ -63,4 +143,58 @@ pub fn vibrate(duration: u32) -> Result<(), &'static str> {
         return Err("Duration must be greater than 0");
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amplitude(controller: &HapticsController) -> u8 {
+        (controller.get_linear().get_amplitude() * 255.0).round() as u8
+    }
+
+    #[test]
+    fn test_custom_frames_play_in_order() {
+        let frames = [HapticFrame::new(40, 10), HapticFrame::new(200, 20), HapticFrame::new(90, 5)];
+        let mut haptics = HapticsController::new();
+        haptics.play(HapticEffect::Custom(&frames), 1000).unwrap();
+        assert_eq!(haptics.current_frame(), Some(frames[0]));
+        assert_eq!(amplitude(&haptics), 40);
+
+        haptics.tick(1009).unwrap();
+        assert_eq!(amplitude(&haptics), 40);
+        haptics.tick(1010).unwrap();
+        assert_eq!(amplitude(&haptics), 200);
+        haptics.tick(1030).unwrap();
+        assert_eq!(amplitude(&haptics), 90);
+        haptics.tick(1035).unwrap();
+        assert!(!haptics.is_playing());
+        assert_eq!(amplitude(&haptics), 0);
+    }
+
+    #[test]
+    fn test_late_tick_skips_elapsed_frames() {
+        let mut haptics = HapticsController::new();
+        haptics.play(HapticEffect::DoubleClick, 0).unwrap();
+        haptics.tick(75).unwrap();
+        assert_eq!(haptics.current_frame(), Some(HapticFrame::new(255, 10)));
+        assert_eq!(haptics.queued_frames(), 0);
+        haptics.tick(80).unwrap();
+        assert!(!haptics.is_playing());
+    }
+
+    #[test]
+    fn test_stop_clears_queue_mid_playback() {
+        let frames = [HapticFrame::new(100, 10), HapticFrame::new(150, 10), HapticFrame::new(200, 10)];
+        let mut haptics = HapticsController::new();
+        haptics.play(HapticEffect::Custom(&frames), 0).unwrap();
+        haptics.tick(12).unwrap();
+        assert_eq!(amplitude(&haptics), 150);
+        haptics.stop().unwrap();
+        assert!(!haptics.is_playing());
+        assert_eq!(haptics.queued_frames(), 0);
+        assert_eq!(amplitude(&haptics), 0);
+        haptics.tick(100).unwrap();
+        assert_eq!(amplitude(&haptics), 0);
+    }
+}
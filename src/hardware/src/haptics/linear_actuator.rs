@@ -1,6 +1,8 @@
 extern crate alloc;
 use alloc::string::String;
 use core::sync::atomic::{AtomicU32, Ordering};
+const AMPLITUDE_SCALE: u32 = 1000;
+const RAMP_MAX_STEPS: u32 = 32;
 pub struct LinearActuator {
     position: AtomicU32,
     frequency: AtomicU32,
@@ -48,6 +50,31 @@ impl LinearActuator {
         let scaled = self.amplitude.load(Ordering::SeqCst);
         scaled as f32 / 1000.0
     }
+    /// Steps the amplitude from its current value to `target` (0-255) over
+    /// `ramp_ms` instead of jumping, which avoids the audible click of a hard
+    /// onset. A zero ramp is the same as an instant set. `wait_ms` is called
+    /// before each step and must block for the given milliseconds, typically
+    /// on the system timer; the waits add up to exactly `ramp_ms`.
+    pub fn set_amplitude_ramped<F: FnMut(u32)>(&self, target: u8, ramp_ms: u16, mut wait_ms: F) -> Result<(), String> {
+        let target = target as u32 * AMPLITUDE_SCALE / u8::MAX as u32;
+        let start = self.amplitude.load(Ordering::SeqCst);
+        let steps = (ramp_ms as u32).min(RAMP_MAX_STEPS);
+        if steps == 0 || start == target {
+            self.amplitude.store(target, Ordering::SeqCst);
+            return Ok(());
+        }
+        let ramp_ms = ramp_ms as u32;
+        for step in 1..=steps {
+            wait_ms(ramp_ms * step / steps - ramp_ms * (step - 1) / steps);
+            let value = if target > start {
+                start + (target - start) * step / steps
+            } else {
+                start - (start - target) * step / steps
+            };
+            self.amplitude.store(value, Ordering::SeqCst);
+        }
+        Ok(())
+    }
     pub fn pulse(&self, _count: u32, _duration_ms: u64) -> Result<(), String> {
         Ok(())
     }
@@ -62,4 +89,65 @@ impl Default for LinearActuator {
 }
 pub fn enable() -> Result<(), &'static str> {
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn ramp(actuator: &LinearActuator, target: u8, ramp_ms: u16) -> (Vec<u32>, u32) {
+        let mut seen = Vec::new();
+        let mut waited = 0;
+        actuator
+            .set_amplitude_ramped(target, ramp_ms, |ms| {
+                seen.push(actuator.amplitude.load(Ordering::SeqCst));
+                waited += ms;
+            })
+            .unwrap();
+        seen.push(actuator.amplitude.load(Ordering::SeqCst));
+        (seen, waited)
+    }
+
+    #[test]
+    fn test_ramp_up_is_monotonic() {
+        let actuator = LinearActuator::new();
+        actuator.set_amplitude(0.0).unwrap();
+        let (seen, waited) = ramp(&actuator, 255, 16);
+        assert_eq!(seen.len(), 17);
+        assert!(seen.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(*seen.last().unwrap(), AMPLITUDE_SCALE);
+        assert_eq!(waited, 16);
+    }
+
+    #[test]
+    fn test_ramp_down_is_monotonic() {
+        let actuator = LinearActuator::new();
+        actuator.set_amplitude(1.0).unwrap();
+        let (seen, _) = ramp(&actuator, 0, 100);
+        assert_eq!(seen.len(), RAMP_MAX_STEPS as usize + 1);
+        assert!(seen.windows(2).all(|w| w[0] > w[1]));
+        assert_eq!(actuator.get_amplitude(), 0.0);
+    }
+
+    #[test]
+    fn test_uneven_ramp_waits_sum_to_ramp_ms() {
+        for ramp_ms in [63, 100] {
+            let actuator = LinearActuator::new();
+            actuator.set_amplitude(0.0).unwrap();
+            let mut waits = Vec::new();
+            actuator.set_amplitude_ramped(255, ramp_ms, |ms| waits.push(ms)).unwrap();
+            assert_eq!(waits.len(), RAMP_MAX_STEPS as usize);
+            assert_eq!(waits.iter().sum::<u32>(), ramp_ms as u32);
+        }
+    }
+
+    #[test]
+    fn test_zero_ramp_is_instant() {
+        let actuator = LinearActuator::new();
+        actuator.set_amplitude(0.0).unwrap();
+        let (seen, waited) = ramp(&actuator, 255, 0);
+        assert_eq!(seen, [AMPLITUDE_SCALE]);
+        assert_eq!(waited, 0);
+    }
+}
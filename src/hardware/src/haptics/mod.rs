@@ -1,6 +1,6 @@
 pub mod vibrator;
 pub mod linear_actuator;
 pub mod haptics_control;
-pub use haptics_control::HapticsController;
+pub use haptics_control::{HapticEffect, HapticFrame, HapticsController};
 pub use vibrator::Vibrator;
 pub use linear_actuator::LinearActuator;
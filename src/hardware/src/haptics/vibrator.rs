@@ -2,15 +2,23 @@ extern crate alloc;
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+/// A pattern registered with `play_pattern`, stepped by `tick`.
+struct PatternPlayback {
+    steps: Vec<(bool, u16)>,
+    index: usize,
+    step_started_ms: Option<u64>,
+}
 pub struct Vibrator {
     enabled: AtomicBool,
     intensity: AtomicU8,
+    playback: spin::Mutex<Option<PatternPlayback>>,
 }
 impl Vibrator {
     pub fn new() -> Self {
         Vibrator {
             enabled: AtomicBool::new(false),
             intensity: AtomicU8::new(200),
+            playback: spin::Mutex::new(None),
         }
     }
     pub fn vibrate(&self, _duration_ms: u64) -> Result<(), String> {
@@ -24,6 +32,57 @@ impl Vibrator {
         }
         Ok(())
     }
+    /// Registers `pattern` (on/off, duration_ms) and returns straight away.
+    /// The first step is applied now; later steps are applied by `tick`,
+    /// whose first call after this anchors the pattern's timeline.
+    pub fn play_pattern(&self, pattern: &[(bool, u16)]) {
+        let mut playback = self.playback.lock();
+        match pattern.first() {
+            Some(&(on, _)) => {
+                self.enabled.store(on, Ordering::SeqCst);
+                *playback = Some(PatternPlayback {
+                    steps: pattern.to_vec(),
+                    index: 0,
+                    step_started_ms: None,
+                });
+            }
+            None => {
+                self.enabled.store(false, Ordering::SeqCst);
+                *playback = None;
+            }
+        }
+    }
+    /// Advances the registered pattern to `now_ms`; called from the main loop.
+    pub fn tick(&self, now_ms: u64) {
+        let mut playback = self.playback.lock();
+        let Some(state) = playback.as_mut() else {
+            return;
+        };
+        let started = *state.step_started_ms.get_or_insert(now_ms);
+        let mut step_start = started;
+        loop {
+            let duration = state.steps[state.index].1 as u64;
+            if now_ms.saturating_sub(step_start) < duration {
+                break;
+            }
+            step_start += duration;
+            state.index += 1;
+            if state.index >= state.steps.len() {
+                self.enabled.store(false, Ordering::SeqCst);
+                *playback = None;
+                return;
+            }
+        }
+        state.step_started_ms = Some(step_start);
+        self.enabled.store(state.steps[state.index].0, Ordering::SeqCst);
+    }
+    pub fn is_playing(&self) -> bool {
+        self.playback.lock().is_some()
+    }
+    pub fn cancel(&self) {
+        *self.playback.lock() = None;
+        self.enabled.store(false, Ordering::SeqCst);
+    }
     pub fn set_intensity(&self, intensity: u8) -> Result<(), String> {
         self.intensity.store(intensity, Ordering::SeqCst);
         Ok(())
@@ -46,3 +105,50 @@ impl Default for Vibrator {
         Self::new()
     }
 }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_pattern_follows_simulated_time() {
+        let vibrator = Vibrator::new();
+        vibrator.play_pattern(&[(true, 100), (false, 50), (true, 200)]);
+        assert!(vibrator.is_playing());
+        assert!(vibrator.is_vibrating());
+        vibrator.tick(1000);
+        assert!(vibrator.is_vibrating());
+        vibrator.tick(1099);
+        assert!(vibrator.is_vibrating());
+        vibrator.tick(1100);
+        assert!(!vibrator.is_vibrating());
+        vibrator.tick(1149);
+        assert!(!vibrator.is_vibrating());
+        vibrator.tick(1150);
+        assert!(vibrator.is_vibrating());
+        vibrator.tick(1349);
+        assert!(vibrator.is_playing());
+        vibrator.tick(1350);
+        assert!(!vibrator.is_vibrating());
+        assert!(!vibrator.is_playing());
+    }
+    #[test]
+    fn test_late_tick_skips_elapsed_steps() {
+        let vibrator = Vibrator::new();
+        vibrator.play_pattern(&[(true, 10), (false, 10), (true, 10), (false, 10)]);
+        vibrator.tick(0);
+        vibrator.tick(25);
+        assert!(vibrator.is_vibrating());
+        vibrator.tick(500);
+        assert!(!vibrator.is_playing());
+    }
+    #[test]
+    fn test_cancel_stops_immediately() {
+        let vibrator = Vibrator::new();
+        vibrator.play_pattern(&[(true, 100), (false, 100)]);
+        vibrator.tick(0);
+        vibrator.cancel();
+        assert!(!vibrator.is_vibrating());
+        assert!(!vibrator.is_playing());
+        vibrator.tick(50);
+        assert!(!vibrator.is_vibrating());
+    }
+}
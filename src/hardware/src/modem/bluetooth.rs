@@ -1,4 +1,5 @@
 use core::ptr::{read_volatile, write_volatile};
+use crate::device_interfaces::mmio::{MmioRegisters, RegisterBank};
 
 const BT_CTRL_OFFSET: u64 = 0x0000;
 const BT_STATUS_OFFSET: u64 = 0x0004;
@@ -8,6 +9,7 @@ const BT_POWER_OFFSET: u64 = 0x0010;
 const BT_SIGNAL_OFFSET: u64 = 0x0014;
 const BT_MODE_OFFSET: u64 = 0x0018;
 const BT_CONFIG_OFFSET: u64 = 0x001C;
+const BT_BASE_FREQ_MHZ: u32 = 2402;
 
 fn bt_reg(offset: u64) -> u64 {
     crate::bt_base() + offset
@@ -104,3 +106,135 @@ pub fn set_config(config: u32) -> Result<(), &'static str> {
 pub fn get_config() -> u32 {
     unsafe { read_volatile(bt_reg(BT_CONFIG_OFFSET) as *const u32) }
 }
+
+/// RF band plan. Both share the 2.4 GHz ISM band starting at 2402 MHz:
+/// BR/EDR hops over 79 channels at 1 MHz spacing, LE over 40 at 2 MHz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Band {
+    Ism24,
+    Ism24LowEnergy,
+}
+
+impl Band {
+    pub fn channel_count(&self) -> u8 {
+        match self {
+            Band::Ism24 => 79,
+            Band::Ism24LowEnergy => 40,
+        }
+    }
+
+    fn spacing_mhz(&self) -> u32 {
+        match self {
+            Band::Ism24 => 1,
+            Band::Ism24LowEnergy => 2,
+        }
+    }
+
+    fn code(&self) -> u32 {
+        match self {
+            Band::Ism24 => 0,
+            Band::Ism24LowEnergy => 1,
+        }
+    }
+
+    pub fn frequency_mhz(&self, channel: u8) -> Option<u32> {
+        if channel >= self.channel_count() {
+            return None;
+        }
+        Some(BT_BASE_FREQ_MHZ + channel as u32 * self.spacing_mhz())
+    }
+}
+
+/// Channel-level control of the radio for adaptive frequency hopping. The
+/// synthesiser is tuned by writing the channel's RF frequency to `bt_freq`.
+pub struct BluetoothRadio<R: RegisterBank = MmioRegisters> {
+    regs: R,
+    band: Band,
+    channel: u8,
+}
+
+impl BluetoothRadio {
+    pub fn new() -> Self {
+        BluetoothRadio::with_registers(MmioRegisters::new(crate::bt_base()))
+    }
+}
+
+impl Default for BluetoothRadio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: RegisterBank> BluetoothRadio<R> {
+    pub fn with_registers(regs: R) -> Self {
+        BluetoothRadio { regs, band: Band::Ism24, channel: 0 }
+    }
+
+    /// Switches band plan and retunes to channel 0 of the new band.
+    pub fn set_band(&mut self, band: Band) {
+        self.band = band;
+        self.regs.write32(BT_BAND_OFFSET, band.code());
+        self.channel = 0;
+        self.regs.write32(BT_FREQ_OFFSET, self.current_frequency_mhz());
+    }
+
+    pub fn band(&self) -> Band {
+        self.band
+    }
+
+    pub fn set_channel(&mut self, channel: u8) -> Result<(), &'static str> {
+        let freq = self.band.frequency_mhz(channel).ok_or("invalid_bt_channel")?;
+        self.regs.write32(BT_FREQ_OFFSET, freq);
+        self.channel = channel;
+        Ok(())
+    }
+
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    pub fn current_frequency_mhz(&self) -> u32 {
+        self.band.frequency_mhz(self.channel).unwrap_or(BT_BASE_FREQ_MHZ)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device_interfaces::mmio::test_support::MemoryBank;
+
+    fn radio() -> BluetoothRadio<MemoryBank> {
+        BluetoothRadio::with_registers(MemoryBank::default())
+    }
+
+    #[test]
+    fn test_channels_map_to_2_4ghz_frequencies() {
+        let mut radio = radio();
+        for (channel, freq) in [(0, 2402), (1, 2403), (39, 2441), (78, 2480)] {
+            radio.set_channel(channel).unwrap();
+            assert_eq!(radio.current_frequency_mhz(), freq);
+            assert_eq!(radio.regs.get(BT_FREQ_OFFSET), freq);
+        }
+
+        radio.set_band(Band::Ism24LowEnergy);
+        assert_eq!(radio.regs.get(BT_BAND_OFFSET), 1);
+        assert_eq!(radio.regs.get(BT_FREQ_OFFSET), 2402);
+        for (channel, freq) in [(0, 2402), (12, 2426), (39, 2480)] {
+            radio.set_channel(channel).unwrap();
+            assert_eq!(radio.current_frequency_mhz(), freq);
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_channel_is_rejected() {
+        let mut radio = radio();
+        radio.set_channel(10).unwrap();
+        assert_eq!(radio.set_channel(79), Err("invalid_bt_channel"));
+        assert_eq!(radio.channel(), 10);
+        assert_eq!(radio.regs.get(BT_FREQ_OFFSET), 2412);
+
+        radio.set_band(Band::Ism24LowEnergy);
+        assert_eq!(radio.set_channel(40), Err("invalid_bt_channel"));
+        assert_eq!(radio.current_frequency_mhz(), 2402);
+    }
+}
@@ -8,6 +8,7 @@ pub mod sim;
 pub mod thread;
 pub mod wifi;
 pub mod zigbee;
+pub use bluetooth::{BluetoothRadio, Band};
 pub use fiveg::FiveG;
 pub use lte::LTE;
 pub use wifi::WiFi;
@@ -0,0 +1,218 @@
+use crate::device_interfaces::mmio::{MappedRegisters, RegisterBank};
+
+fn payment_status_reg() -> u64 { crate::payment_status_reg() }
+fn payment_amount_reg() -> u64 { crate::payment_amount_reg() }
+fn payment_currency_reg() -> u64 { crate::payment_currency_reg() }
+fn payment_security_reg() -> u64 { crate::payment_security_reg() }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum PaymentState {
+    Idle = 0,
+    Selecting = 1,
+    Authenticating = 2,
+    Authorized = 3,
+    Completed = 4,
+    Failed = 5,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentError {
+    InvalidTransition,
+    InvalidAmount,
+    AuthenticationFailed,
+}
+
+const PAYMENT_STATUS_OFFSET: u64 = 0x0;
+const PAYMENT_AMOUNT_OFFSET: u64 = 0x4;
+const PAYMENT_CURRENCY_OFFSET: u64 = 0x8;
+const PAYMENT_SECURITY_OFFSET: u64 = 0xC;
+
+/// Maps the payment driver's offsets onto the board's payment registers.
+fn payment_reg(offset: u64) -> u64 {
+    match offset {
+        PAYMENT_STATUS_OFFSET => payment_status_reg(),
+        PAYMENT_AMOUNT_OFFSET => payment_amount_reg(),
+        PAYMENT_CURRENCY_OFFSET => payment_currency_reg(),
+        _ => payment_security_reg(),
+    }
+}
+
+/// Payment transaction driven through
+/// `Idle -> Selecting -> Authenticating -> Authorized -> Completed/Failed`.
+/// Every transition is mirrored to the status register; out-of-order calls
+/// are rejected without touching the hardware.
+pub struct NFCPayment<R: RegisterBank = MappedRegisters> {
+    regs: R,
+    state: PaymentState,
+    amount: u32,
+    currency: u16,
+}
+
+impl NFCPayment {
+    pub fn new() -> Self {
+        NFCPayment::with_registers(MappedRegisters::new(payment_reg))
+    }
+}
+
+impl<R: RegisterBank> NFCPayment<R> {
+    pub fn with_registers(regs: R) -> Self {
+        NFCPayment { regs, state: PaymentState::Idle, amount: 0, currency: 0 }
+    }
+
+    pub fn state(&self) -> PaymentState {
+        self.state
+    }
+
+    pub fn amount(&self) -> u32 {
+        self.amount
+    }
+
+    pub fn currency(&self) -> u16 {
+        self.currency
+    }
+
+    fn transition(&mut self, next: PaymentState) {
+        self.state = next;
+        self.regs.write32(PAYMENT_STATUS_OFFSET, next as u32);
+    }
+
+    /// Starts a transaction for `amount` minor units of the ISO 4217 numeric
+    /// `currency`. Allowed from `Idle` or after a finished transaction.
+    pub fn begin(&mut self, amount: u32, currency: u16) -> Result<(), PaymentError> {
+        match self.state {
+            PaymentState::Idle | PaymentState::Completed | PaymentState::Failed => {}
+            _ => return Err(PaymentError::InvalidTransition),
+        }
+        if amount == 0 {
+            return Err(PaymentError::InvalidAmount);
+        }
+        self.amount = amount;
+        self.currency = currency;
+        self.regs.write32(PAYMENT_AMOUNT_OFFSET, amount);
+        self.regs.write32(PAYMENT_CURRENCY_OFFSET, currency as u32);
+        self.transition(PaymentState::Selecting);
+        Ok(())
+    }
+
+    pub fn authorize(&mut self, token: &[u8]) -> Result<(), PaymentError> {
+        if self.state != PaymentState::Selecting {
+            return Err(PaymentError::InvalidTransition);
+        }
+        self.transition(PaymentState::Authenticating);
+        if token.is_empty() {
+            self.transition(PaymentState::Failed);
+            return Err(PaymentError::AuthenticationFailed);
+        }
+        let mut word = [0u8; 4];
+        let len = token.len().min(4);
+        word[..len].copy_from_slice(&token[..len]);
+        self.regs.write32(PAYMENT_SECURITY_OFFSET, u32::from_le_bytes(word));
+        self.transition(PaymentState::Authorized);
+        Ok(())
+    }
+
+    pub fn complete(&mut self) -> Result<(), PaymentError> {
+        if self.state != PaymentState::Authorized {
+            return Err(PaymentError::InvalidTransition);
+        }
+        self.transition(PaymentState::Completed);
+        Ok(())
+    }
+
+    pub fn cancel(&mut self) {
+        self.amount = 0;
+        self.currency = 0;
+        self.regs.write32(PAYMENT_AMOUNT_OFFSET, 0);
+        self.transition(PaymentState::Idle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+
+    #[derive(Default)]
+    struct Recorder {
+        writes: RefCell<Vec<(u64, u32)>>,
+    }
+
+    impl Recorder {
+        fn wrote(&self, write: (u64, u32)) -> bool {
+            self.writes.borrow().contains(&write)
+        }
+    }
+
+    impl RegisterBank for Recorder {
+        fn read32(&self, offset: u64) -> u32 {
+            self.writes.borrow().iter().rev().find(|(o, _)| *o == offset).map_or(0, |(_, v)| *v)
+        }
+
+        fn write32(&self, offset: u64, value: u32) {
+            self.writes.borrow_mut().push((offset, value));
+        }
+    }
+
+    fn payment() -> NFCPayment<Recorder> {
+        NFCPayment::with_registers(Recorder::default())
+    }
+
+    fn last_status(p: &NFCPayment<Recorder>) -> Option<u32> {
+        p.regs.writes.borrow().iter().rev().find(|(o, _)| *o == PAYMENT_STATUS_OFFSET).map(|(_, v)| *v)
+    }
+
+    #[test]
+    fn test_happy_path() {
+        let mut p = payment();
+        p.begin(1250, 978).unwrap();
+        assert_eq!(p.state(), PaymentState::Selecting);
+        assert!(p.regs.wrote((PAYMENT_AMOUNT_OFFSET, 1250)));
+        assert!(p.regs.wrote((PAYMENT_CURRENCY_OFFSET, 978)));
+        p.authorize(b"tok1").unwrap();
+        assert_eq!(p.state(), PaymentState::Authorized);
+        p.complete().unwrap();
+        assert_eq!(p.state(), PaymentState::Completed);
+        assert_eq!(last_status(&p), Some(PaymentState::Completed as u32));
+        p.begin(10, 840).unwrap();
+        assert_eq!(p.state(), PaymentState::Selecting);
+    }
+
+    #[test]
+    fn test_illegal_transitions_rejected() {
+        let mut p = payment();
+        assert_eq!(p.authorize(b"tok"), Err(PaymentError::InvalidTransition));
+        assert_eq!(p.complete(), Err(PaymentError::InvalidTransition));
+        assert!(p.regs.writes.borrow().is_empty());
+
+        p.begin(100, 978).unwrap();
+        assert_eq!(p.begin(200, 978), Err(PaymentError::InvalidTransition));
+        assert_eq!(p.complete(), Err(PaymentError::InvalidTransition));
+
+        p.authorize(b"tok").unwrap();
+        assert_eq!(p.authorize(b"tok"), Err(PaymentError::InvalidTransition));
+        assert_eq!(p.begin(100, 978), Err(PaymentError::InvalidTransition));
+
+        p.complete().unwrap();
+        assert_eq!(p.complete(), Err(PaymentError::InvalidTransition));
+        assert_eq!(p.begin(0, 978), Err(PaymentError::InvalidAmount));
+    }
+
+    #[test]
+    fn test_failed_authentication_and_cancel() {
+        let mut p = payment();
+        p.begin(100, 978).unwrap();
+        assert_eq!(p.authorize(&[]), Err(PaymentError::AuthenticationFailed));
+        assert_eq!(p.state(), PaymentState::Failed);
+        assert_eq!(p.complete(), Err(PaymentError::InvalidTransition));
+
+        p.begin(100, 978).unwrap();
+        p.authorize(b"tok").unwrap();
+        p.cancel();
+        assert_eq!(p.state(), PaymentState::Idle);
+        assert_eq!(p.amount(), 0);
+        assert_eq!(last_status(&p), Some(PaymentState::Idle as u32));
+    }
+}
@@ -1,4 +1,5 @@
 use core::ptr::{read_volatile, write_volatile};
+use crate::device_interfaces::mmio::{MappedRegisters, RegisterBank};
 
 fn nfc_command_reg() -> u64 { crate::nfc_command_reg() }
 fn nfc_response_reg() -> u64 { crate::nfc_response_reg() }
@@ -9,11 +10,110 @@ fn reader_detect_reg() -> u64 { crate::reader_detect_reg() }
 fn uid_reg() -> u64 { crate::uid_reg() }
 fn whitelist_reg() -> u64 { crate::whitelist_reg() }
 
-pub struct NFCReader;
+pub const NFC_UID_LEN: usize = 7;
+pub const MAX_WHITELIST_ENTRIES: usize = 5;
+const WHITELIST_ENTRY_STRIDE: u64 = 8;
+
+pub type NfcUid = [u8; NFC_UID_LEN];
+
+const READER_STATUS_OFFSET: u64 = 0x00;
+const READER_UID_OFFSET: u64 = 0x08;
+const READER_WHITELIST_OFFSET: u64 = 0x10;
+const STATUS_TAG_PRESENT: u32 = 0x1;
+
+/// Maps the reader's driver offsets onto the status, UID and whitelist
+/// registers of the board map.
+fn reader_reg(offset: u64) -> u64 {
+    match offset {
+        READER_STATUS_OFFSET => nfc_status_reg(),
+        READER_UID_OFFSET => uid_reg(),
+        _ => whitelist_reg() + (offset - READER_WHITELIST_OFFSET),
+    }
+}
+
+pub struct NFCReader<R: RegisterBank = MappedRegisters> {
+    regs: R,
+    whitelist: [NfcUid; MAX_WHITELIST_ENTRIES],
+    whitelist_len: usize,
+}
+
+impl<R: RegisterBank> NFCReader<R> {
+    pub fn with_registers(regs: R) -> Self {
+        NFCReader { regs, whitelist: [[0; NFC_UID_LEN]; MAX_WHITELIST_ENTRIES], whitelist_len: 0 }
+    }
+
+    /// The UID register holds a 7-byte ISO 14443 UID in its low bytes,
+    /// least significant first.
+    pub fn read_uid(&self) -> Option<NfcUid> {
+        if self.regs.read32(READER_STATUS_OFFSET) & STATUS_TAG_PRESENT == 0 {
+            return None;
+        }
+        let raw = self.regs.read64(READER_UID_OFFSET).to_le_bytes();
+        let mut uid = [0u8; NFC_UID_LEN];
+        uid.copy_from_slice(&raw[..NFC_UID_LEN]);
+        Some(uid)
+    }
+
+    pub fn whitelist(&self) -> &[NfcUid] {
+        &self.whitelist[..self.whitelist_len]
+    }
+
+    pub fn is_whitelisted(&self, uid: &NfcUid) -> bool {
+        self.whitelist().contains(uid)
+    }
+
+    pub fn add_to_whitelist(&mut self, uid: NfcUid) -> Result<(), &'static str> {
+        if self.is_whitelisted(&uid) {
+            return Ok(());
+        }
+        if self.whitelist_len == MAX_WHITELIST_ENTRIES {
+            return Err("whitelist_full");
+        }
+        self.whitelist[self.whitelist_len] = uid;
+        self.whitelist_len += 1;
+        self.sync_whitelist();
+        Ok(())
+    }
+
+    pub fn remove_from_whitelist(&mut self, uid: &NfcUid) -> Result<bool, &'static str> {
+        let Some(idx) = self.whitelist().iter().position(|u| u == uid) else {
+            return Ok(false);
+        };
+        self.whitelist.copy_within(idx + 1..self.whitelist_len, idx);
+        self.whitelist_len -= 1;
+        self.sync_whitelist();
+        Ok(true)
+    }
+
+    pub fn clear_whitelist(&mut self) -> Result<(), &'static str> {
+        self.whitelist_len = 0;
+        self.sync_whitelist();
+        Ok(())
+    }
+
+    /// Mirrors the in-memory list into the whitelist registers; unused
+    /// slots are zeroed so stale entries never linger in hardware.
+    fn sync_whitelist(&mut self) {
+        for slot in 0..MAX_WHITELIST_ENTRIES {
+            let mut raw = [0u8; 8];
+            if slot < self.whitelist_len {
+                raw[..NFC_UID_LEN].copy_from_slice(&self.whitelist[slot]);
+            }
+            let offset = READER_WHITELIST_OFFSET + slot as u64 * WHITELIST_ENTRY_STRIDE;
+            self.regs.write64(offset, u64::from_le_bytes(raw));
+        }
+    }
+
+    /// Returns the UID of the tag in the field only if it is whitelisted.
+    /// An empty whitelist admits nothing.
+    pub fn read_if_whitelisted(&self) -> Option<NfcUid> {
+        self.read_uid().filter(|uid| self.is_whitelisted(uid))
+    }
+}
 
 impl NFCReader {
     pub fn new() -> Self {
-        NFCReader
+        NFCReader::with_registers(MappedRegisters::new(reader_reg))
     }
 
     pub fn init() -> Result<(), &'static str> {
@@ -116,4 +216,60 @@ pub fn get_uid() -> Result<u32, &'static str> {
     unsafe {
         Ok(read_volatile(uid_reg() as *const u32))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device_interfaces::mmio::test_support::MemoryBank;
+
+    fn registers(uid: Option<u64>) -> MemoryBank {
+        let regs = MemoryBank::default();
+        if let Some(raw) = uid {
+            regs.set(READER_STATUS_OFFSET, STATUS_TAG_PRESENT);
+            regs.write64(READER_UID_OFFSET, raw);
+        }
+        regs
+    }
+
+    const ALLOWED: NfcUid = [0x04, 0xA1, 0xB2, 0xC3, 0xD4, 0xE5, 0x80];
+    const ALLOWED_RAW: u64 = 0x0080_E5D4_C3B2_A104;
+
+    #[test]
+    fn test_whitelisted_uid_is_returned() {
+        let mut reader = NFCReader::with_registers(registers(Some(ALLOWED_RAW)));
+        assert_eq!(reader.read_uid(), Some(ALLOWED));
+        reader.add_to_whitelist(ALLOWED).unwrap();
+        assert_eq!(reader.read_if_whitelisted(), Some(ALLOWED));
+        assert_eq!(reader.regs.read64(READER_WHITELIST_OFFSET), ALLOWED_RAW);
+    }
+
+    #[test]
+    fn test_unlisted_uid_is_rejected() {
+        let mut reader = NFCReader::with_registers(registers(Some(0x0011_2233_4455_6677)));
+        reader.add_to_whitelist(ALLOWED).unwrap();
+        assert!(reader.read_uid().is_some());
+        assert_eq!(reader.read_if_whitelisted(), None);
+        assert!(reader.remove_from_whitelist(&ALLOWED).unwrap());
+        assert_eq!(reader.regs.read64(READER_WHITELIST_OFFSET), 0);
+    }
+
+    #[test]
+    fn test_empty_whitelist_rejects_all() {
+        let reader = NFCReader::with_registers(registers(Some(ALLOWED_RAW)));
+        assert!(reader.whitelist().is_empty());
+        assert_eq!(reader.read_if_whitelisted(), None);
+        let absent = NFCReader::with_registers(registers(None));
+        assert_eq!(absent.read_if_whitelisted(), None);
+    }
+
+    #[test]
+    fn test_whitelist_capacity() {
+        let mut reader = NFCReader::with_registers(registers(None));
+        for i in 0..MAX_WHITELIST_ENTRIES as u8 {
+            reader.add_to_whitelist([i; NFC_UID_LEN]).unwrap();
+        }
+        assert_eq!(reader.add_to_whitelist([0xFF; NFC_UID_LEN]), Err("whitelist_full"));
+        assert!(reader.add_to_whitelist([0; NFC_UID_LEN]).is_ok());
+    }
+}
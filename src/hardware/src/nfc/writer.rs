@@ -1,4 +1,5 @@
 use core::ptr::{read_volatile, write_volatile};
+use crate::device_interfaces::mmio::{MappedRegisters, RegisterBank};
 
 fn nfc_command_reg() -> u64 { crate::nfc_command_reg() }
 fn nfc_fifo_reg() -> u64 { crate::nfc_fifo_reg() }
@@ -8,11 +9,113 @@ fn writer_erase_reg() -> u64 { crate::writer_erase_reg() }
 fn write_data_reg() -> u64 { crate::write_data_reg() }
 fn write_addr_reg() -> u64 { crate::write_addr_reg() }
 
-pub struct NFCWriter;
+pub const NFC_BLOCK_SIZE: usize = 16;
+const NFC_PAGE_SIZE: usize = 4;
+const PAGES_PER_BLOCK: u32 = (NFC_BLOCK_SIZE / NFC_PAGE_SIZE) as u32;
+const MAX_PAGE: u32 = 0xFFFF;
+const ERASE_POLL_LIMIT: u32 = 5000;
+const WRITE_POLL_LIMIT: u32 = 1000;
+const CMD_WRITE: u32 = 0x2;
+const CMD_READ: u32 = 0x3;
+const ERASE_BLOCK: u32 = 0x2;
+const STATUS_WRITE_DONE: u32 = 0x2;
+const STATUS_ERASE_DONE: u32 = 0x4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NfcWriteError {
+    BlockTooLarge,
+    AddressOutOfRange,
+    EraseTimeout,
+    WriteTimeout,
+    VerifyMismatch,
+}
+
+const WRITER_COMMAND_OFFSET: u64 = 0x00;
+const WRITER_STATUS_OFFSET: u64 = 0x04;
+const WRITER_ERASE_OFFSET: u64 = 0x08;
+const WRITER_DATA_OFFSET: u64 = 0x0C;
+const WRITER_ADDR_OFFSET: u64 = 0x10;
+
+/// Maps the writer's driver offsets onto the shared NFC command/status
+/// registers and the writer's own erase, data and address registers.
+fn writer_reg(offset: u64) -> u64 {
+    match offset {
+        WRITER_COMMAND_OFFSET => nfc_command_reg(),
+        WRITER_STATUS_OFFSET => nfc_status_reg(),
+        WRITER_ERASE_OFFSET => writer_erase_reg(),
+        WRITER_DATA_OFFSET => write_data_reg(),
+        _ => write_addr_reg(),
+    }
+}
+
+pub struct NFCWriter<R: RegisterBank = MappedRegisters> {
+    regs: R,
+}
+
+impl<R: RegisterBank> NFCWriter<R> {
+    pub fn with_registers(regs: R) -> Self {
+        NFCWriter { regs }
+    }
+
+    /// Done bits are sticky and write-one-to-clear; clearing them before
+    /// each operation keeps a completion left over from the previous one
+    /// from satisfying the next poll.
+    fn clear_status(&self, mask: u32) {
+        self.regs.write32(WRITER_STATUS_OFFSET, mask);
+    }
+
+    fn poll_status(&self, mask: u32, limit: u32) -> bool {
+        (0..limit).any(|_| self.regs.read32(WRITER_STATUS_OFFSET) & mask != 0)
+    }
+
+    /// Erases block `addr`, waits for the erase to finish, writes `data`
+    /// (zero-padded to the block) and reads every page back. Writing over a
+    /// block that never finished erasing would corrupt it, so an erase
+    /// timeout aborts before any data goes out.
+    pub fn write_block(&mut self, addr: u32, data: &[u8]) -> Result<(), NfcWriteError> {
+        if data.len() > NFC_BLOCK_SIZE {
+            return Err(NfcWriteError::BlockTooLarge);
+        }
+        let first_page = addr
+            .checked_mul(PAGES_PER_BLOCK)
+            .filter(|p| p + PAGES_PER_BLOCK - 1 <= MAX_PAGE)
+            .ok_or(NfcWriteError::AddressOutOfRange)?;
+
+        self.clear_status(STATUS_ERASE_DONE);
+        self.regs.write32(WRITER_ADDR_OFFSET, first_page);
+        self.regs.write32(WRITER_ERASE_OFFSET, ERASE_BLOCK);
+        if !self.poll_status(STATUS_ERASE_DONE, ERASE_POLL_LIMIT) {
+            return Err(NfcWriteError::EraseTimeout);
+        }
+
+        let mut block = [0u8; NFC_BLOCK_SIZE];
+        block[..data.len()].copy_from_slice(data);
+        let words = block.chunks_exact(NFC_PAGE_SIZE).map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]));
+
+        for (page, word) in (first_page..).zip(words.clone()) {
+            self.regs.write32(WRITER_ADDR_OFFSET, page);
+            self.regs.write32(WRITER_DATA_OFFSET, word);
+            self.clear_status(STATUS_WRITE_DONE);
+            self.regs.write32(WRITER_COMMAND_OFFSET, CMD_WRITE);
+            if !self.poll_status(STATUS_WRITE_DONE, WRITE_POLL_LIMIT) {
+                return Err(NfcWriteError::WriteTimeout);
+            }
+        }
+
+        for (page, word) in (first_page..).zip(words) {
+            self.regs.write32(WRITER_ADDR_OFFSET, page);
+            self.regs.write32(WRITER_COMMAND_OFFSET, CMD_READ);
+            if self.regs.read32(WRITER_DATA_OFFSET) != word {
+                return Err(NfcWriteError::VerifyMismatch);
+            }
+        }
+        Ok(())
+    }
+}
 
 impl NFCWriter {
     pub fn new() -> Self {
-        NFCWriter
+        NFCWriter::with_registers(MappedRegisters::new(writer_reg))
     }
 
     pub fn init() -> Result<(), &'static str> {
@@ -111,3 +214,115 @@ impl NFCWriter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::{Cell, RefCell};
+
+    const PAGES: usize = 64;
+
+    /// Register model of a tag: commands act on `pages` immediately unless
+    /// the erase is stuck or a page is set to flip a bit on write.
+    struct MockTag {
+        pages: RefCell<[u32; PAGES]>,
+        addr: Cell<u32>,
+        data: Cell<u32>,
+        status: Cell<u32>,
+        erase_stuck: Cell<bool>,
+        corrupt_page: Option<u32>,
+    }
+
+    impl MockTag {
+        fn new() -> Self {
+            MockTag {
+                pages: RefCell::new([0xFFFF_FFFF; PAGES]),
+                addr: Cell::new(0),
+                data: Cell::new(0),
+                status: Cell::new(0),
+                erase_stuck: Cell::new(false),
+                corrupt_page: None,
+            }
+        }
+
+        fn page(&self, page: usize) -> u32 {
+            self.pages.borrow()[page]
+        }
+    }
+
+    impl RegisterBank for MockTag {
+        fn read32(&self, offset: u64) -> u32 {
+            match offset {
+                WRITER_STATUS_OFFSET => self.status.get(),
+                WRITER_DATA_OFFSET => self.data.get(),
+                WRITER_ADDR_OFFSET => self.addr.get(),
+                _ => 0,
+            }
+        }
+
+        fn write32(&self, offset: u64, value: u32) {
+            let addr = self.addr.get();
+            match offset {
+                WRITER_ADDR_OFFSET => self.addr.set(value),
+                WRITER_DATA_OFFSET => self.data.set(value),
+                WRITER_STATUS_OFFSET => self.status.set(self.status.get() & !value),
+                WRITER_ERASE_OFFSET if !self.erase_stuck.get() => {
+                    let start = addr as usize;
+                    self.pages.borrow_mut()[start..start + PAGES_PER_BLOCK as usize].fill(0);
+                    self.status.set(self.status.get() | STATUS_ERASE_DONE);
+                }
+                WRITER_COMMAND_OFFSET if value == CMD_WRITE => {
+                    let flip = if self.corrupt_page == Some(addr) { 1 } else { 0 };
+                    self.pages.borrow_mut()[addr as usize] = self.data.get() ^ flip;
+                    self.status.set(self.status.get() | STATUS_WRITE_DONE);
+                }
+                WRITER_COMMAND_OFFSET if value == CMD_READ => self.data.set(self.page(addr as usize)),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_block_success() {
+        let mut writer = NFCWriter::with_registers(MockTag::new());
+        writer.write_block(2, b"hello nfc!").unwrap();
+        assert_eq!(writer.regs.page(8), u32::from_le_bytes(*b"hell"));
+        assert_eq!(writer.regs.page(10), u32::from_le_bytes([b'c', b'!', 0, 0]));
+        assert_eq!(writer.regs.page(11), 0);
+        assert_eq!(writer.regs.page(12), 0xFFFF_FFFF);
+    }
+
+    #[test]
+    fn test_erase_timeout_aborts_before_writing() {
+        let tag = MockTag::new();
+        tag.erase_stuck.set(true);
+        let mut writer = NFCWriter::with_registers(tag);
+        assert_eq!(writer.write_block(1, b"data"), Err(NfcWriteError::EraseTimeout));
+        assert_eq!(writer.regs.page(4), 0xFFFF_FFFF);
+    }
+
+    #[test]
+    fn test_stale_erase_done_does_not_satisfy_next_erase() {
+        let mut writer = NFCWriter::with_registers(MockTag::new());
+        writer.write_block(0, b"first").unwrap();
+        writer.regs.erase_stuck.set(true);
+        assert_eq!(writer.write_block(1, b"second"), Err(NfcWriteError::EraseTimeout));
+        assert_eq!(writer.regs.page(4), 0xFFFF_FFFF);
+        assert_eq!(writer.regs.page(0), u32::from_le_bytes(*b"firs"));
+    }
+
+    #[test]
+    fn test_readback_mismatch() {
+        let mut tag = MockTag::new();
+        tag.corrupt_page = Some(5);
+        let mut writer = NFCWriter::with_registers(tag);
+        assert_eq!(writer.write_block(1, &[0xAA; NFC_BLOCK_SIZE]), Err(NfcWriteError::VerifyMismatch));
+    }
+
+    #[test]
+    fn test_rejects_oversized_data() {
+        let mut writer = NFCWriter::with_registers(MockTag::new());
+        assert_eq!(writer.write_block(0, &[0u8; NFC_BLOCK_SIZE + 1]), Err(NfcWriteError::BlockTooLarge));
+        assert_eq!(writer.write_block(u32::MAX, b"x"), Err(NfcWriteError::AddressOutOfRange));
+    }
+}
@@ -0,0 +1,172 @@
+use crate::device_interfaces::i2c::{I2CBus, I2CInterface};
+
+/// Largest SoC change, in percent, accepted from a single raw reading beyond
+/// what coulomb counting already predicts. Anything larger is treated as a
+/// measurement glitch and clamped.
+pub const MAX_SOC_JUMP_PERCENT: f32 = 5.0;
+const MEASUREMENT_WEIGHT: f32 = 0.2;
+const VOLTAGE_BLEND: f32 = 0.3;
+const EMPTY_MV: f32 = 3400.0;
+const FULL_MV: f32 = 4200.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatteryRegisters {
+    pub i2c_addr: u8,
+    pub soc: u8,
+    pub voltage: u8,
+    pub current: u8,
+}
+
+/// Stable battery percentage from the PMIC fuel gauge. Each update predicts
+/// the new SoC from the measured current (coulomb counting), blends the raw
+/// SoC register with a voltage-based estimate, clamps implausible jumps and
+/// then nudges the prediction towards that measurement.
+pub struct BatteryGauge<B: I2CBus = I2CInterface> {
+    bus: B,
+    regs: BatteryRegisters,
+    capacity_mah: u32,
+    smoothed: Option<f32>,
+    clamped_samples: u32,
+}
+
+impl<B: I2CBus> BatteryGauge<B> {
+    pub fn new(bus: B, regs: BatteryRegisters, capacity_mah: u32) -> Self {
+        BatteryGauge { bus, regs, capacity_mah: capacity_mah.max(1), smoothed: None, clamped_samples: 0 }
+    }
+
+    pub fn from_config(bus: B, config: &crate::config::HardwareConfig) -> Self {
+        let regs = BatteryRegisters {
+            i2c_addr: config.registers.battery_i2c_addr,
+            soc: config.registers.battery_reg_soc,
+            voltage: config.registers.battery_reg_voltage,
+            current: config.registers.battery_reg_current,
+        };
+        BatteryGauge::new(bus, regs, config.power.battery_capacity_mah)
+    }
+
+    fn read_reg(&mut self, reg: u8) -> Result<u16, &'static str> {
+        let mut buf = [0u8; 2];
+        self.bus.write(self.regs.i2c_addr, &[reg])?;
+        self.bus.read(self.regs.i2c_addr, &mut buf)?;
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    /// Samples the gauge registers; `elapsed_ms` is the time since the
+    /// previous update. Returns the smoothed SoC.
+    pub fn update(&mut self, elapsed_ms: u32) -> Result<u8, &'static str> {
+        let raw_soc = (self.read_reg(self.regs.soc)? as f32).min(100.0);
+        let voltage_mv = self.read_reg(self.regs.voltage)? as f32;
+        let current_ma = self.read_reg(self.regs.current)? as i16 as f32;
+
+        let voltage_soc = ((voltage_mv - EMPTY_MV) / (FULL_MV - EMPTY_MV) * 100.0).clamp(0.0, 100.0);
+        let measured = raw_soc * (1.0 - VOLTAGE_BLEND) + voltage_soc * VOLTAGE_BLEND;
+
+        let next = match self.smoothed {
+            None => measured,
+            Some(previous) => {
+                let charge_mah = current_ma * elapsed_ms as f32 / 3_600_000.0;
+                let predicted = previous + charge_mah / self.capacity_mah as f32 * 100.0;
+                let low = predicted - MAX_SOC_JUMP_PERCENT;
+                let high = predicted + MAX_SOC_JUMP_PERCENT;
+                let bounded = measured.clamp(low, high);
+                if bounded != measured {
+                    self.clamped_samples = self.clamped_samples.saturating_add(1);
+                }
+                predicted + (bounded - predicted) * MEASUREMENT_WEIGHT
+            }
+        };
+        let next = next.clamp(0.0, 100.0);
+        self.smoothed = Some(next);
+        Ok(Self::round_percent(next))
+    }
+
+    pub fn smoothed_soc_percent(&self) -> u8 {
+        self.smoothed.map(Self::round_percent).unwrap_or(0)
+    }
+
+    /// Number of raw readings clamped as implausible jumps.
+    pub fn clamped_samples(&self) -> u32 {
+        self.clamped_samples
+    }
+
+    fn round_percent(value: f32) -> u8 {
+        (value + 0.5) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGS: BatteryRegisters = BatteryRegisters { i2c_addr: 0x55, soc: 0x02, voltage: 0x08, current: 0x0C };
+
+    struct FuelGauge {
+        soc: u16,
+        voltage_mv: u16,
+        current_ma: i16,
+        selected: u8,
+    }
+
+    impl I2CBus for FuelGauge {
+        fn write(&mut self, _address: u8, data: &[u8]) -> Result<(), &'static str> {
+            self.selected = data[0];
+            Ok(())
+        }
+
+        fn read(&mut self, _address: u8, buf: &mut [u8]) -> Result<(), &'static str> {
+            let value = match self.selected {
+                0x02 => self.soc,
+                0x08 => self.voltage_mv,
+                0x0C => self.current_ma as u16,
+                _ => return Err("unknown_register"),
+            };
+            buf.copy_from_slice(&value.to_le_bytes());
+            Ok(())
+        }
+    }
+
+    fn gauge(soc: u16, voltage_mv: u16) -> BatteryGauge<FuelGauge> {
+        BatteryGauge::new(FuelGauge { soc, voltage_mv, current_ma: 0, selected: 0 }, REGS, 5000)
+    }
+
+    #[test]
+    fn test_smoothing_follows_trend_without_spikes() {
+        let mut g = gauge(60, 3880);
+        assert_eq!(g.update(0).unwrap(), 60);
+        let noisy = [58, 63, 57, 62, 59, 61, 58, 60];
+        for soc in noisy {
+            g.bus.soc = soc;
+            let out = g.update(1000).unwrap();
+            assert!((58..=62).contains(&out), "spiky output {}", out);
+        }
+        for step in 0..40 {
+            g.bus.soc = 60 - step / 4;
+            g.bus.voltage_mv = 3880 - step * 2;
+            g.bus.current_ma = -500;
+            g.update(1000).unwrap();
+        }
+        assert!(g.smoothed_soc_percent() < 58);
+    }
+
+    #[test]
+    fn test_impossible_jump_is_clamped() {
+        let mut g = gauge(80, 4040);
+        g.update(0).unwrap();
+        g.bus.soc = 5;
+        g.bus.voltage_mv = 3420;
+        let out = g.update(1000).unwrap();
+        assert_eq!(g.clamped_samples(), 1);
+        assert!(out >= 79, "jump leaked through: {}", out);
+    }
+
+    #[test]
+    fn test_bus_error_propagates() {
+        let mut g = BatteryGauge::new(
+            FuelGauge { soc: 50, voltage_mv: 3800, current_ma: 0, selected: 0 },
+            BatteryRegisters { soc: 0x7F, ..REGS },
+            5000,
+        );
+        assert_eq!(g.update(0), Err("unknown_register"));
+        assert_eq!(g.smoothed_soc_percent(), 0);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::device_interfaces::i2c::{I2CBus, I2CInterface};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChargeError {
+    ThermalLimited,
+    Bus(&'static str),
+}
+
+impl From<&'static str> for ChargeError {
+    fn from(err: &'static str) -> Self {
+        ChargeError::Bus(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChargeLimits {
+    pub max_current_ma: u16,
+    pub max_voltage_mv: u16,
+    pub throttle_temp: i8,
+}
+
+/// PMIC charger front end that never programs more than the configured
+/// current/voltage ceilings. While the battery is at or above the throttle
+/// temperature the charge current may be lowered but not raised.
+pub struct ChargeController<B: I2CBus = I2CInterface> {
+    bus: B,
+    i2c_addr: u8,
+    current_reg: u8,
+    voltage_reg: u8,
+    limits: ChargeLimits,
+    temperature: i8,
+    current_ma: u16,
+    voltage_mv: u16,
+}
+
+impl<B: I2CBus> ChargeController<B> {
+    pub fn new(bus: B, i2c_addr: u8, current_reg: u8, voltage_reg: u8, limits: ChargeLimits) -> Self {
+        ChargeController {
+            bus,
+            i2c_addr,
+            current_reg,
+            voltage_reg,
+            limits,
+            temperature: 25,
+            current_ma: 0,
+            voltage_mv: 0,
+        }
+    }
+
+    pub fn from_config(bus: B, config: &crate::config::HardwareConfig) -> Self {
+        let limits = ChargeLimits {
+            max_current_ma: config.power.max_charge_current_ma,
+            max_voltage_mv: config.power.max_charge_voltage_mv,
+            throttle_temp: config.thermal.throttle_temp,
+        };
+        ChargeController::new(
+            bus,
+            config.registers.battery_i2c_addr,
+            config.registers.pmic_chg_current,
+            config.registers.pmic_chg_voltage,
+            limits,
+        )
+    }
+
+    pub fn update_temperature(&mut self, temp_celsius: i8) {
+        self.temperature = temp_celsius;
+    }
+
+    pub fn is_thermal_limited(&self) -> bool {
+        self.temperature >= self.limits.throttle_temp
+    }
+
+    fn write_reg(&mut self, reg: u8, value: u16) -> Result<(), ChargeError> {
+        let [lo, hi] = value.to_le_bytes();
+        self.bus.write(self.i2c_addr, &[reg, lo, hi])?;
+        Ok(())
+    }
+
+    /// Programs the charge current, clamped to the ceiling. Returns the
+    /// value actually written.
+    pub fn set_charge_current(&mut self, ma: u16) -> Result<u16, ChargeError> {
+        let target = ma.min(self.limits.max_current_ma);
+        if target > self.current_ma && self.is_thermal_limited() {
+            return Err(ChargeError::ThermalLimited);
+        }
+        self.write_reg(self.current_reg, target)?;
+        self.current_ma = target;
+        Ok(target)
+    }
+
+    /// Programs the charge voltage, clamped to the ceiling. Returns the
+    /// value actually written.
+    pub fn set_charge_voltage(&mut self, mv: u16) -> Result<u16, ChargeError> {
+        let target = mv.min(self.limits.max_voltage_mv);
+        self.write_reg(self.voltage_reg, target)?;
+        self.voltage_mv = target;
+        Ok(target)
+    }
+
+    pub fn charge_current_ma(&self) -> u16 {
+        self.current_ma
+    }
+
+    pub fn charge_voltage_mv(&self) -> u16 {
+        self.voltage_mv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use super::*;
+    use alloc::vec::Vec;
+
+    struct Pmic {
+        writes: Vec<(u8, u16)>,
+    }
+
+    impl I2CBus for Pmic {
+        fn write(&mut self, _address: u8, data: &[u8]) -> Result<(), &'static str> {
+            self.writes.push((data[0], u16::from_le_bytes([data[1], data[2]])));
+            Ok(())
+        }
+
+        fn read(&mut self, _address: u8, _buf: &mut [u8]) -> Result<(), &'static str> {
+            Ok(())
+        }
+    }
+
+    const LIMITS: ChargeLimits = ChargeLimits { max_current_ma: 3000, max_voltage_mv: 4400, throttle_temp: 85 };
+
+    fn controller() -> ChargeController<Pmic> {
+        ChargeController::new(Pmic { writes: Vec::new() }, 0x6B, 0x04, 0x05, LIMITS)
+    }
+
+    #[test]
+    fn test_requests_above_ceiling_are_clamped() {
+        let mut chg = controller();
+        assert_eq!(chg.set_charge_current(5000), Ok(3000));
+        assert_eq!(chg.set_charge_voltage(4600), Ok(4400));
+        assert_eq!(chg.set_charge_current(1500), Ok(1500));
+        assert_eq!(chg.bus.writes, [(0x04, 3000), (0x05, 4400), (0x04, 1500)]);
+    }
+
+    #[test]
+    fn test_high_temperature_blocks_current_increase() {
+        let mut chg = controller();
+        chg.set_charge_current(1000).unwrap();
+        chg.update_temperature(90);
+        assert_eq!(chg.set_charge_current(2000), Err(ChargeError::ThermalLimited));
+        assert_eq!(chg.charge_current_ma(), 1000);
+        assert_eq!(chg.set_charge_current(500), Ok(500));
+        assert_eq!(chg.bus.writes.len(), 2);
+    }
+
+    #[test]
+    fn test_cooling_reenables_current_increase() {
+        let mut chg = controller();
+        chg.update_temperature(86);
+        assert_eq!(chg.set_charge_current(2000), Err(ChargeError::ThermalLimited));
+        chg.update_temperature(60);
+        assert_eq!(chg.set_charge_current(2000), Ok(2000));
+        assert!(!chg.is_thermal_limited());
+    }
+}
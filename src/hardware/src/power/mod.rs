@@ -0,0 +1,4 @@
+pub mod battery_gauge;
+pub mod charging;
+pub use battery_gauge::{BatteryGauge, BatteryRegisters};
+pub use charging::{ChargeController, ChargeError, ChargeLimits};
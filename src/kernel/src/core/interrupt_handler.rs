@@ -2,6 +2,7 @@ use alloc::vec::Vec;
 use parking_lot::Mutex;
 use alloc::sync::Arc;
 use alloc::boxed::Box;
+use core::sync::atomic::{AtomicU64, Ordering};
 
 #[derive(Clone, Debug)]
 pub struct TimerConfig {
@@ -25,6 +26,9 @@ pub enum TimerMode {
     OneShot,
     Periodic,
     Continuous,
+    /// Timers expiring within `window_us` of the earliest pending one are
+    /// serviced by a single wakeup, delayed by at most `window_us`.
+    Coalesced { window_us: u64 },
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -35,9 +39,14 @@ pub enum InterruptPriority {
     Low = 3,
 }
 
+/// Upper bounds (exclusive) of the lateness buckets: <1ms, 1-5ms, 5-20ms, >=20ms.
+pub const LATENESS_BUCKET_BOUNDS_US: [u64; 3] = [1_000, 5_000, 20_000];
+
 pub struct DeadlineMissDetector {
     deadline_violations: Arc<Mutex<u32>>,
     max_allowed_violations: u32,
+    lateness_buckets: [AtomicU64; 4],
+    max_lateness_us: AtomicU64,
 }
 
 impl DeadlineMissDetector {
@@ -45,9 +54,39 @@ impl DeadlineMissDetector {
         Self {
             deadline_violations: Arc::new(Mutex::new(0)),
             max_allowed_violations,
+            lateness_buckets: [
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+            ],
+            max_lateness_us: AtomicU64::new(0),
         }
     }
 
+    pub fn record_deadline_miss_with_lateness(&self, lateness_us: u64) -> Result<(), &'static str> {
+        let bucket = LATENESS_BUCKET_BOUNDS_US
+            .iter()
+            .position(|&bound| lateness_us < bound)
+            .unwrap_or(LATENESS_BUCKET_BOUNDS_US.len());
+        self.lateness_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.max_lateness_us.fetch_max(lateness_us, Ordering::Relaxed);
+        self.record_deadline_miss()
+    }
+
+    pub fn histogram(&self) -> [u64; 4] {
+        [
+            self.lateness_buckets[0].load(Ordering::Relaxed),
+            self.lateness_buckets[1].load(Ordering::Relaxed),
+            self.lateness_buckets[2].load(Ordering::Relaxed),
+            self.lateness_buckets[3].load(Ordering::Relaxed),
+        ]
+    }
+
+    pub fn max_lateness_us(&self) -> u64 {
+        self.max_lateness_us.load(Ordering::Relaxed)
+    }
+
     pub fn record_deadline_miss(&self) -> Result<(), &'static str> {
         let mut violations = self.deadline_violations.lock();
         *violations += 1;
@@ -60,6 +99,10 @@ impl DeadlineMissDetector {
 
     pub fn reset(&self) {
         *self.deadline_violations.lock() = 0;
+        for bucket in self.lateness_buckets.iter() {
+            bucket.store(0, Ordering::Relaxed);
+        }
+        self.max_lateness_us.store(0, Ordering::Relaxed);
     }
 
     pub fn violation_count(&self) -> u32 {
@@ -73,6 +116,10 @@ pub struct PreemptiveTimerController {
     current_tick: Arc<Mutex<u64>>,
     callbacks: Arc<Mutex<Vec<Box<dyn Fn() + Send + Sync>>>>,
     deadline_detector: DeadlineMissDetector,
+    pending_timers: Arc<Mutex<Vec<(u64, u32)>>>,
+    timer_wakeups: AtomicU64,
+    timers_serviced: AtomicU64,
+    coalesced_wakeups: AtomicU64,
 }
 
 impl PreemptiveTimerController {
@@ -83,6 +130,10 @@ impl PreemptiveTimerController {
             current_tick: Arc::new(Mutex::new(0)),
             callbacks: Arc::new(Mutex::new(Vec::new())),
             deadline_detector: DeadlineMissDetector::new(5),
+            pending_timers: Arc::new(Mutex::new(Vec::new())),
+            timer_wakeups: AtomicU64::new(0),
+            timers_serviced: AtomicU64::new(0),
+            coalesced_wakeups: AtomicU64::new(0),
         }
     }
 
@@ -124,6 +175,71 @@ impl PreemptiveTimerController {
         Ok(())
     }
 
+    pub fn schedule_timer(&self, timer_id: u32, expires_at_us: u64) {
+        self.pending_timers.lock().push((expires_at_us, timer_id));
+    }
+
+    pub fn pending_timer_count(&self) -> usize {
+        self.pending_timers.lock().len()
+    }
+
+    /// Time of the next hardware wakeup, accounting for the coalescing window.
+    pub fn next_wakeup_us(&self) -> Option<u64> {
+        let earliest = self.pending_timers.lock().iter().map(|(at, _)| *at).min()?;
+        match self.get_mode() {
+            TimerMode::Coalesced { window_us } => Some(earliest.saturating_add(window_us)),
+            _ => Some(earliest),
+        }
+    }
+
+    /// Performs every wakeup due at `now_us` and returns the serviced timer ids
+    /// in expiry order. Registered callbacks run once per wakeup.
+    pub fn fire_due_timers(&self, now_us: u64) -> Vec<u32> {
+        let mut fired = Vec::new();
+        while let Some(wakeup_at) = self.next_wakeup_us() {
+            if wakeup_at > now_us {
+                break;
+            }
+
+            let mut batch: Vec<(u64, u32)> = {
+                let mut pending = self.pending_timers.lock();
+                let (due, rest): (Vec<_>, Vec<_>) =
+                    pending.drain(..).partition(|(at, _)| *at <= wakeup_at);
+                *pending = rest;
+                due
+            };
+            batch.sort();
+
+            self.timer_wakeups.fetch_add(1, Ordering::Relaxed);
+            self.timers_serviced.fetch_add(batch.len() as u64, Ordering::Relaxed);
+            if let TimerMode::Coalesced { .. } = self.get_mode() {
+                self.coalesced_wakeups.fetch_add(1, Ordering::Relaxed);
+            }
+
+            for callback in self.callbacks.lock().iter() {
+                callback();
+            }
+            fired.extend(batch.into_iter().map(|(_, id)| id));
+        }
+        fired
+    }
+
+    pub fn timer_wakeups(&self) -> u64 {
+        self.timer_wakeups.load(Ordering::Relaxed)
+    }
+
+    pub fn coalesced_wakeups(&self) -> u64 {
+        self.coalesced_wakeups.load(Ordering::Relaxed)
+    }
+
+    pub fn timers_per_wakeup_avg(&self) -> f32 {
+        let wakeups = self.timer_wakeups();
+        if wakeups == 0 {
+            return 0.0;
+        }
+        self.timers_serviced.load(Ordering::Relaxed) as f32 / wakeups as f32
+    }
+
     pub fn current_tick(&self) -> u64 {
         *self.current_tick.lock()
     }
@@ -148,4 +264,71 @@ impl PreemptiveTimerController {
     pub fn get_deadline_violations(&self) -> u32 {
         self.deadline_detector.violation_count()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lateness_lands_in_expected_buckets() {
+        let detector = DeadlineMissDetector::new(100);
+        for lateness in [0, 999, 1_000, 4_999, 5_000, 19_999, 20_000, 75_000] {
+            detector.record_deadline_miss_with_lateness(lateness).unwrap();
+        }
+
+        assert_eq!(detector.histogram(), [2, 2, 2, 2]);
+        assert_eq!(detector.max_lateness_us(), 75_000);
+        assert_eq!(detector.violation_count(), 8);
+    }
+
+    fn controller(mode: TimerMode) -> PreemptiveTimerController {
+        let controller = PreemptiveTimerController::new(TimerConfig::new(1_000, InterruptPriority::High));
+        controller.set_mode(mode);
+        controller
+    }
+
+    #[test]
+    fn coalesced_timers_share_one_wakeup() {
+        let controller = controller(TimerMode::Coalesced { window_us: 100 });
+        controller.schedule_timer(1, 100);
+        controller.schedule_timer(2, 150);
+        controller.schedule_timer(3, 180);
+        controller.schedule_timer(4, 500);
+
+        assert_eq!(controller.next_wakeup_us(), Some(200));
+        assert!(controller.fire_due_timers(199).is_empty());
+        assert_eq!(controller.fire_due_timers(200), [1, 2, 3]);
+        assert_eq!(controller.timer_wakeups(), 1);
+
+        assert_eq!(controller.fire_due_timers(600), [4]);
+        assert_eq!(controller.timer_wakeups(), 2);
+        assert_eq!(controller.coalesced_wakeups(), 2);
+        assert_eq!(controller.timers_per_wakeup_avg(), 2.0);
+        assert_eq!(controller.pending_timer_count(), 0);
+    }
+
+    #[test]
+    fn periodic_mode_wakes_per_timer() {
+        let controller = controller(TimerMode::Periodic);
+        controller.schedule_timer(1, 100);
+        controller.schedule_timer(2, 150);
+
+        assert_eq!(controller.fire_due_timers(200), [1, 2]);
+        assert_eq!(controller.timer_wakeups(), 2);
+        assert_eq!(controller.coalesced_wakeups(), 0);
+        assert_eq!(controller.timers_per_wakeup_avg(), 1.0);
+    }
+
+    #[test]
+    fn reset_clears_histogram() {
+        let detector = DeadlineMissDetector::new(1);
+        detector.record_deadline_miss_with_lateness(2_500).unwrap();
+        assert!(detector.record_deadline_miss_with_lateness(30_000).is_err());
+
+        detector.reset();
+        assert_eq!(detector.histogram(), [0; 4]);
+        assert_eq!(detector.max_lateness_us(), 0);
+        assert_eq!(detector.violation_count(), 0);
+    }
+}
@@ -7,6 +7,6 @@ pub mod irq_fiq;
 
 pub use async_io::{IoFuture, AsyncExecutor, IoMultiplexer};
 pub use multicore::{CpuAffinity, LoadBalancer, WorkQueue};
-pub use multicore_advanced::{CpuCluster, CoreWorkQueue, LoadPredictor, WorkStealingScheduler};
+pub use multicore_advanced::{CpuCluster, CoreWorkQueue, LoadPredictor, WorkStealingScheduler, StealPolicy};
 pub use interrupt_handler::{PreemptiveTimerController, TimerConfig, TimerMode, InterruptPriority, DeadlineMissDetector};
 pub use irq_fiq::{InterruptController, InterruptType, InterruptPriority as IrqPriority, InterruptContext};
@@ -1,5 +1,6 @@
 use alloc::vec::Vec;
 use alloc::vec;
+use alloc::collections::BTreeMap;
 use parking_lot::Mutex;
 use alloc::sync::Arc;
 
@@ -44,6 +45,9 @@ pub struct LoadBalancer {
     cpu_loads: Arc<Mutex<Vec<u32>>>,
     total_tasks: Arc<Mutex<u32>>,
     rebalance_threshold: u32,
+    online: Arc<Mutex<Vec<bool>>>,
+    affinities: Arc<Mutex<BTreeMap<u64, CpuAffinity>>>,
+    fallback_placements: Arc<Mutex<u32>>,
 }
 
 impl LoadBalancer {
@@ -52,9 +56,72 @@ impl LoadBalancer {
             cpu_loads: Arc::new(Mutex::new(vec![0; cpu_count])),
             total_tasks: Arc::new(Mutex::new(0)),
             rebalance_threshold,
+            online: Arc::new(Mutex::new(vec![true; cpu_count])),
+            affinities: Arc::new(Mutex::new(BTreeMap::new())),
+            fallback_placements: Arc::new(Mutex::new(0)),
         }
     }
 
+    pub fn set_cpu_online(&self, cpu_id: usize, online: bool) -> Result<(), &'static str> {
+        let mut states = self.online.lock();
+        if cpu_id >= states.len() {
+            return Err("Invalid CPU ID");
+        }
+        states[cpu_id] = online;
+        Ok(())
+    }
+
+    pub fn is_cpu_online(&self, cpu_id: usize) -> bool {
+        self.online.lock().get(cpu_id).copied().unwrap_or(false)
+    }
+
+    pub fn set_affinity_mask(&self, task_id: u64, mask: u64) {
+        self.affinities.lock().insert(task_id, CpuAffinity::from_mask(mask));
+    }
+
+    pub fn affinity(&self, task_id: u64) -> CpuAffinity {
+        self.affinities
+            .lock()
+            .get(&task_id)
+            .copied()
+            .unwrap_or_else(CpuAffinity::any_cpu)
+    }
+
+    /// Places `task_id` on the least-loaded online CPU allowed by its mask.
+    /// If none of the allowed CPUs is online, any online CPU is used instead
+    /// and the placement is counted in `fallback_placements`.
+    pub fn place_task(&self, task_id: u64) -> Result<usize, &'static str> {
+        let affinity = self.affinity(task_id);
+        let cpu_id = {
+            let loads = self.cpu_loads.lock();
+            let online = self.online.lock();
+            let least_loaded = |allowed: &dyn Fn(usize) -> bool| {
+                loads
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| online[*idx] && allowed(*idx))
+                    .min_by_key(|(_, &load)| load)
+                    .map(|(idx, _)| idx)
+            };
+
+            match least_loaded(&|idx| idx < 64 && affinity.has_cpu(idx as u32)) {
+                Some(idx) => idx,
+                None => {
+                    let idx = least_loaded(&|_| true).ok_or("No online CPU")?;
+                    *self.fallback_placements.lock() += 1;
+                    idx
+                }
+            }
+        };
+
+        self.add_task(cpu_id)?;
+        Ok(cpu_id)
+    }
+
+    pub fn fallback_placements(&self) -> u32 {
+        *self.fallback_placements.lock()
+    }
+
     pub fn add_task(&self, cpu_id: usize) -> Result<(), &'static str> {
         let mut loads = self.cpu_loads.lock();
         if cpu_id >= loads.len() {
@@ -184,4 +251,53 @@ impl WorkQueue {
     pub fn total_depth(&self) -> usize {
         self.queue_depths.lock().iter().sum()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placement_respects_mask() {
+        let balancer = LoadBalancer::new(8, 2);
+        let big_cores = 0b1111_0000;
+        balancer.set_affinity_mask(42, big_cores);
+        balancer.add_task(4).unwrap();
+        balancer.add_task(5).unwrap();
+        balancer.add_task(7).unwrap();
+
+        assert_eq!(balancer.place_task(42).unwrap(), 6);
+        let next = balancer.place_task(42).unwrap();
+        assert!((4..8).contains(&next));
+        assert_eq!(balancer.fallback_placements(), 0);
+    }
+
+    #[test]
+    fn placement_skips_offline_cores_in_mask() {
+        let balancer = LoadBalancer::new(4, 2);
+        balancer.set_affinity_mask(1, 0b1100);
+        balancer.set_cpu_online(2, false).unwrap();
+        assert_eq!(balancer.place_task(1).unwrap(), 3);
+        assert_eq!(balancer.fallback_placements(), 0);
+    }
+
+    #[test]
+    fn placement_falls_back_when_mask_is_offline() {
+        let balancer = LoadBalancer::new(4, 2);
+        balancer.set_affinity_mask(9, 0b0011);
+        balancer.set_cpu_online(0, false).unwrap();
+        balancer.set_cpu_online(1, false).unwrap();
+        balancer.add_task(2).unwrap();
+
+        assert_eq!(balancer.place_task(9).unwrap(), 3);
+        assert_eq!(balancer.fallback_placements(), 1);
+    }
+
+    #[test]
+    fn placement_fails_without_online_cores() {
+        let balancer = LoadBalancer::new(2, 2);
+        balancer.set_cpu_online(0, false).unwrap();
+        balancer.set_cpu_online(1, false).unwrap();
+        assert!(balancer.place_task(3).is_err());
+    }
+}
@@ -190,26 +190,70 @@ impl LoadPredictor {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum StealPolicy {
+    #[default]
+    RandomVictim,
+    NearestNeighbor,
+    MostLoaded,
+}
+
 pub struct WorkStealingScheduler {
     work_queues: Arc<Mutex<Vec<VecDeque<u64>>>>,
     steal_attempts: Arc<Mutex<u32>>,
     successful_steals: Arc<Mutex<u32>>,
+    policy: Arc<Mutex<StealPolicy>>,
+    core_clusters: Arc<Mutex<Vec<u32>>>,
+    load_predictors: Vec<LoadPredictor>,
+    victim_seed: Arc<Mutex<u64>>,
 }
 
 impl WorkStealingScheduler {
     pub fn new(core_count: usize) -> Self {
         let mut queues = Vec::with_capacity(core_count);
+        let mut predictors = Vec::with_capacity(core_count);
         for _ in 0..core_count {
             queues.push(VecDeque::new());
+            predictors.push(LoadPredictor::new(8));
         }
 
         Self {
             work_queues: Arc::new(Mutex::new(queues)),
             steal_attempts: Arc::new(Mutex::new(0)),
             successful_steals: Arc::new(Mutex::new(0)),
+            policy: Arc::new(Mutex::new(StealPolicy::default())),
+            core_clusters: Arc::new(Mutex::new(Vec::new())),
+            load_predictors: predictors,
+            victim_seed: Arc::new(Mutex::new(0x9E37_79B9_7F4A_7C15)),
         }
     }
 
+    pub fn with_policy(core_count: usize, policy: StealPolicy) -> Self {
+        let scheduler = Self::new(core_count);
+        scheduler.set_policy(policy);
+        scheduler
+    }
+
+    pub fn set_policy(&self, policy: StealPolicy) {
+        *self.policy.lock() = policy;
+    }
+
+    pub fn policy(&self) -> StealPolicy {
+        *self.policy.lock()
+    }
+
+    /// Maps cores to clusters in order: the first `core_count()` cores belong
+    /// to the first cluster, and so on. Used by `StealPolicy::NearestNeighbor`.
+    pub fn set_topology(&self, clusters: &[CpuCluster]) {
+        let mut map = Vec::new();
+        for cluster in clusters {
+            for _ in 0..cluster.core_count() {
+                map.push(cluster.cluster_id());
+            }
+        }
+        *self.core_clusters.lock() = map;
+    }
+
     pub fn enqueue(&self, core_id: usize, task_id: u64) -> Result<(), &'static str> {
         let mut queues = self.work_queues.lock();
         if core_id >= queues.len() {
@@ -245,6 +289,76 @@ impl WorkStealingScheduler {
         Ok(None)
     }
 
+    /// Steals one task for `thief_core` from a victim chosen by the current policy.
+    pub fn steal_for(&self, thief_core: usize) -> Result<Option<u64>, &'static str> {
+        let victim = {
+            let queues = self.work_queues.lock();
+            if thief_core >= queues.len() {
+                return Err("Invalid core ID");
+            }
+            self.select_victim(&queues, thief_core)
+        };
+
+        match victim {
+            Some(victim_core) => self.steal_work(victim_core, thief_core),
+            None => {
+                *self.steal_attempts.lock() += 1;
+                Ok(None)
+            }
+        }
+    }
+
+    fn select_victim(&self, queues: &[VecDeque<u64>], thief_core: usize) -> Option<usize> {
+        for (core, queue) in queues.iter().enumerate() {
+            self.load_predictors[core].record_load(queue.len() as u32);
+        }
+
+        let candidates: Vec<usize> = (0..queues.len())
+            .filter(|&core| core != thief_core && !queues[core].is_empty())
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        match self.policy() {
+            StealPolicy::RandomVictim => {
+                let idx = (self.next_victim_seed() % candidates.len() as u64) as usize;
+                Some(candidates[idx])
+            }
+            StealPolicy::MostLoaded => candidates
+                .iter()
+                .copied()
+                .max_by_key(|&core| (self.load_predictors[core].predict_load(), queues[core].len())),
+            StealPolicy::NearestNeighbor => {
+                let clusters = self.core_clusters.lock();
+                let cluster_of = |core: usize| clusters.get(core).copied();
+                let home = cluster_of(thief_core);
+                candidates.iter().copied().min_by_key(|&core| {
+                    let same_cluster = home.is_some() && cluster_of(core) == home;
+                    (!same_cluster, core.abs_diff(thief_core))
+                })
+            }
+        }
+    }
+
+    fn next_victim_seed(&self) -> u64 {
+        let mut seed = self.victim_seed.lock();
+        let mut x = *seed;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        *seed = x;
+        x
+    }
+
+    pub fn steal_attempts(&self) -> u32 {
+        *self.steal_attempts.lock()
+    }
+
+    pub fn steal_successes(&self) -> u32 {
+        *self.successful_steals.lock()
+    }
+
     pub fn steal_statistics(&self) -> (u32, u32, f32) {
         let attempts = *self.steal_attempts.lock();
         let successes = *self.successful_steals.lock();
@@ -256,7 +370,75 @@ impl WorkStealingScheduler {
         (attempts, successes, success_rate)
     }
 
+    pub fn queue_depth(&self, core_id: usize) -> Result<usize, &'static str> {
+        let queues = self.work_queues.lock();
+        if core_id >= queues.len() {
+            return Err("Invalid core ID");
+        }
+        Ok(queues[core_id].len())
+    }
+
     pub fn total_work(&self) -> usize {
         self.work_queues.lock().iter().map(|q| q.len()).sum()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(scheduler: &WorkStealingScheduler, core: usize, count: u64) {
+        for task in 0..count {
+            scheduler.enqueue(core, core as u64 * 100 + task).unwrap();
+        }
+    }
+
+    #[test]
+    fn default_policy_is_random_victim() {
+        let scheduler = WorkStealingScheduler::new(4);
+        assert_eq!(scheduler.policy(), StealPolicy::RandomVictim);
+        fill(&scheduler, 1, 3);
+        assert!(scheduler.steal_for(0).unwrap().is_some());
+        assert_eq!(scheduler.steal_attempts(), 1);
+        assert_eq!(scheduler.steal_successes(), 1);
+    }
+
+    #[test]
+    fn most_loaded_drains_heaviest_queue() {
+        let scheduler = WorkStealingScheduler::with_policy(4, StealPolicy::MostLoaded);
+        fill(&scheduler, 0, 2);
+        fill(&scheduler, 1, 8);
+        fill(&scheduler, 2, 4);
+
+        for _ in 0..4 {
+            let task = scheduler.steal_for(3).unwrap().unwrap();
+            assert_eq!(task / 100, 1);
+        }
+
+        assert_eq!(scheduler.queue_depth(0).unwrap(), 2);
+        assert_eq!(scheduler.queue_depth(1).unwrap(), 4);
+        assert_eq!(scheduler.queue_depth(2).unwrap(), 4);
+        assert_eq!(scheduler.steal_attempts(), 4);
+        assert_eq!(scheduler.steal_successes(), 4);
+    }
+
+    #[test]
+    fn nearest_neighbor_prefers_same_cluster() {
+        let scheduler = WorkStealingScheduler::with_policy(4, StealPolicy::NearestNeighbor);
+        scheduler.set_topology(&[CpuCluster::new(0, 2, 1800), CpuCluster::new(1, 2, 2400)]);
+        fill(&scheduler, 1, 1);
+        fill(&scheduler, 2, 5);
+
+        assert_eq!(scheduler.steal_for(0).unwrap(), Some(100));
+        assert_eq!(scheduler.steal_for(0).unwrap().map(|t| t / 100), Some(2));
+    }
+
+    #[test]
+    fn steal_with_no_victims_counts_attempt() {
+        let scheduler = WorkStealingScheduler::with_policy(2, StealPolicy::MostLoaded);
+        assert_eq!(scheduler.steal_for(0).unwrap(), None);
+        assert_eq!(scheduler.steal_attempts(), 1);
+        assert_eq!(scheduler.steal_successes(), 0);
+        assert!(scheduler.steal_for(5).is_err());
+    }
+}
@@ -19,7 +19,7 @@ pub use sync::{Mutex, Priority, FairScheduler, InterruptController, AsyncTaskPoo
 
 pub mod scheduler;
 pub use scheduler::{
-    RtTask, RtEdfScheduler, SlaMetrics, DynamicPriorityManager, ConditionVariable,
+    RtTask, RtEdfScheduler, SlaMetrics, DynamicPriorityManager, ConditionVariable, WaitResult,
     FastRtTask, FastEdfScheduler, FastSlaMetrics,
     PreemptionContext, ContextSwitchTracker,
     TimeBudget, PreemptionDeadline, AdvancedPreemptionContext, TaskSla
@@ -29,7 +29,7 @@ pub mod core;
 pub use core::{
     IoFuture, AsyncExecutor, IoMultiplexer,
     CpuAffinity, LoadBalancer, WorkQueue,
-    CpuCluster, CoreWorkQueue, LoadPredictor, WorkStealingScheduler,
+    CpuCluster, CoreWorkQueue, LoadPredictor, WorkStealingScheduler, StealPolicy,
     PreemptiveTimerController, TimerConfig, TimerMode, InterruptPriority, DeadlineMissDetector
 };
 
@@ -1,7 +1,12 @@
-use alloc::collections::BinaryHeap;
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BinaryHeap};
 use core::cmp::Reverse;
-use core::sync::atomic::AtomicU32;
-use parking_lot::Mutex;
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use parking_lot::{Mutex, MutexGuard};
+
+use crate::sync::Priority;
+
+pub type DeadlineMissCallback = Box<dyn FnMut(&RtTask) + Send>;
 
 #[derive(Clone, Copy, Debug)]
 pub struct RtTask {
@@ -50,32 +55,307 @@ impl SlaMetrics {
 
 pub struct RtEdfScheduler {
     tasks: Mutex<BinaryHeap<Reverse<(u64, u32)>>>,
+    pending: Mutex<BTreeMap<u32, RtTask>>,
     metrics: Mutex<SlaMetrics>,
     deadline_misses: AtomicU32,
+    on_deadline_miss: Mutex<Option<DeadlineMissCallback>>,
 }
 
 impl RtEdfScheduler {
     pub fn new() -> Self {
         RtEdfScheduler {
             tasks: Mutex::new(BinaryHeap::new()),
+            pending: Mutex::new(BTreeMap::new()),
             metrics: Mutex::new(SlaMetrics::new()),
             deadline_misses: AtomicU32::new(0),
+            on_deadline_miss: Mutex::new(None),
         }
     }
 
     pub fn add_task(&self, task: RtTask) {
         let mut tasks = self.tasks.lock();
         tasks.push(Reverse((task.deadline_us, task.id)));
+        self.pending.lock().insert(task.id, task);
     }
 
     pub fn get_task_count(&self) -> usize {
         self.tasks.lock().len()
     }
 
+    /// Installs a callback fired synchronously each time `dispatch_next`
+    /// finds a task already past its deadline. Replaces any previous one.
+    pub fn on_deadline_miss(&self, callback: DeadlineMissCallback) {
+        *self.on_deadline_miss.lock() = Some(callback);
+    }
+
+    pub fn clear_deadline_miss_callback(&self) {
+        *self.on_deadline_miss.lock() = None;
+    }
+
+    /// Pops the earliest-deadline task at simulated time `now_us` and
+    /// accounts it as met or missed in the SLA metrics.
+    pub fn dispatch_next(&self, now_us: u64) -> Option<RtTask> {
+        let Reverse((deadline_us, id)) = self.tasks.lock().pop()?;
+        let task = self
+            .pending
+            .lock()
+            .remove(&id)
+            .unwrap_or_else(|| RtTask::new(id, deadline_us, 0, 0, deadline_us));
+
+        let missed = now_us > task.deadline_us;
+        {
+            let mut metrics = self.metrics.lock();
+            metrics.total_scheduled = metrics.total_scheduled.saturating_add(1);
+            if missed {
+                metrics.deadline_missed = metrics.deadline_missed.saturating_add(1);
+            } else {
+                metrics.deadline_met = metrics.deadline_met.saturating_add(1);
+            }
+        }
+
+        if missed {
+            self.deadline_misses.fetch_add(1, Ordering::Relaxed);
+            self.notify_deadline_miss(&task);
+        }
+        Some(task)
+    }
+
+    pub fn get_deadline_misses(&self) -> u32 {
+        self.deadline_misses.load(Ordering::Relaxed)
+    }
+
+    // The callback is taken out of its slot while it runs so no scheduler
+    // lock is held: it may re-enter the scheduler, and if it panics the
+    // metrics are already committed and the slot simply stays empty.
+    fn notify_deadline_miss(&self, task: &RtTask) {
+        let callback = self.on_deadline_miss.lock().take();
+        if let Some(mut callback) = callback {
+            callback(task);
+            let mut slot = self.on_deadline_miss.lock();
+            if slot.is_none() {
+                *slot = Some(callback);
+            }
+        }
+    }
+
     pub fn get_sla_metrics(&self) -> SlaMetrics {
         *self.metrics.lock()
     }
 }
 
-pub struct DynamicPriorityManager;
-pub struct ConditionVariable;
\ No newline at end of file
+/// Base priorities plus the priority each task inherits through the PI
+/// mutexes it holds. Inheritance is recorded per lock, so releasing one lock
+/// keeps whatever is still owed to waiters on the other locks the task holds.
+pub struct DynamicPriorityManager {
+    base: Mutex<BTreeMap<u32, Priority>>,
+    inherited: Mutex<BTreeMap<usize, (u32, Priority)>>,
+    current: AtomicU32,
+}
+
+impl DynamicPriorityManager {
+    pub fn new() -> Self {
+        DynamicPriorityManager {
+            base: Mutex::new(BTreeMap::new()),
+            inherited: Mutex::new(BTreeMap::new()),
+            current: AtomicU32::new(0),
+        }
+    }
+
+    pub fn register(&self, task_id: u32, priority: Priority) {
+        self.base.lock().insert(task_id, priority);
+    }
+
+    pub fn unregister(&self, task_id: u32) {
+        self.base.lock().remove(&task_id);
+        self.inherited.lock().retain(|_, (owner, _)| *owner != task_id);
+    }
+
+    /// Called by the scheduler on every switch; a plain `lock()` on a PI
+    /// mutex records this task as the owner.
+    pub fn set_current_task(&self, task_id: u32) {
+        self.current.store(task_id, Ordering::Release);
+    }
+
+    pub fn current_task(&self) -> u32 {
+        self.current.load(Ordering::Acquire)
+    }
+
+    pub fn base_priority(&self, task_id: u32) -> Priority {
+        self.base.lock().get(&task_id).copied().unwrap_or(Priority::Normal)
+    }
+
+    pub fn effective_priority(&self, task_id: u32) -> Priority {
+        let base = self.base_priority(task_id);
+        self.inherited
+            .lock()
+            .values()
+            .filter(|(owner, _)| *owner == task_id)
+            .map(|&(_, priority)| priority)
+            .fold(base, Priority::max)
+    }
+
+    /// Records that a waiter of `priority` is blocked on `lock`, held by
+    /// `owner`. Returns true if the owner's effective priority changed.
+    pub fn inherit(&self, lock: usize, owner: u32, priority: Priority) -> bool {
+        if priority <= self.base_priority(owner) {
+            return false;
+        }
+        let before = self.effective_priority(owner);
+        {
+            let mut inherited = self.inherited.lock();
+            let entry = inherited.entry(lock).or_insert((owner, priority));
+            if entry.0 != owner {
+                *entry = (owner, priority);
+            } else if priority > entry.1 {
+                entry.1 = priority;
+            }
+        }
+        self.effective_priority(owner) > before
+    }
+
+    /// Drops what `owner` inherited through `lock`; boosts from other locks
+    /// it still holds are kept.
+    pub fn release(&self, lock: usize, owner: u32) {
+        let mut inherited = self.inherited.lock();
+        if inherited.get(&lock).is_some_and(|&(held_by, _)| held_by == owner) {
+            inherited.remove(&lock);
+        }
+    }
+
+    pub fn is_boosted(&self, task_id: u32) -> bool {
+        self.effective_priority(task_id) > self.base_priority(task_id)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WaitResult {
+    Notified,
+    TimedOut,
+}
+
+/// Spin-based condition variable. Time is read from the injected millisecond
+/// clock so timed waits work against either the hardware timer or a test clock.
+pub struct ConditionVariable {
+    generation: AtomicU64,
+    clock: fn() -> u64,
+}
+
+impl ConditionVariable {
+    pub fn new(clock: fn() -> u64) -> Self {
+        ConditionVariable {
+            generation: AtomicU64::new(0),
+            clock,
+        }
+    }
+
+    pub fn wait<T: ?Sized>(&self, guard: &mut MutexGuard<'_, T>) {
+        self.wait_timeout(guard, u64::MAX);
+    }
+
+    /// Releases `guard` until a notification or until `timeout_ms` elapses,
+    /// then re-acquires it. The guard is held again whatever the result.
+    pub fn wait_timeout<T: ?Sized>(&self, guard: &mut MutexGuard<'_, T>, timeout_ms: u64) -> WaitResult {
+        let observed = self.generation.load(Ordering::Acquire);
+        let deadline = (self.clock)().saturating_add(timeout_ms);
+        MutexGuard::unlocked(guard, || loop {
+            if self.generation.load(Ordering::Acquire) != observed {
+                return WaitResult::Notified;
+            }
+            if (self.clock)() >= deadline {
+                return WaitResult::TimedOut;
+            }
+            core::hint::spin_loop();
+        })
+    }
+
+    /// Wakes every waiter; each re-checks its predicate after re-locking.
+    pub fn notify(&self) {
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn deadline_miss_callback_receives_late_task() {
+        let scheduler = RtEdfScheduler::new();
+        let seen: Arc<Mutex<Vec<(u32, u64)>>> = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        scheduler.on_deadline_miss(Box::new(move |task: &RtTask| {
+            sink.lock().push((task.id, task.deadline_us));
+        }));
+
+        scheduler.add_task(RtTask::new(1, 1_000, 0, 10, 1_000));
+        scheduler.add_task(RtTask::new(2, 5_000, 0, 10, 5_000));
+
+        let first = scheduler.dispatch_next(2_000).unwrap();
+        assert_eq!(first.id, 1);
+        let second = scheduler.dispatch_next(3_000).unwrap();
+        assert_eq!(second.id, 2);
+
+        assert_eq!(seen.lock().as_slice(), &[(1, 1_000)]);
+        let metrics = scheduler.get_sla_metrics();
+        assert_eq!(metrics.deadline_missed, 1);
+        assert_eq!(metrics.deadline_met, 1);
+        assert_eq!(metrics.total_scheduled, 2);
+        assert_eq!(scheduler.get_deadline_misses(), 1);
+    }
+
+    static TEST_CLOCK_MS: AtomicU64 = AtomicU64::new(0);
+
+    fn ticking_clock() -> u64 {
+        TEST_CLOCK_MS.fetch_add(1, Ordering::Relaxed)
+    }
+
+    #[test]
+    fn wait_timeout_returns_notified() {
+        extern crate std;
+        let state = Arc::new(Mutex::new(false));
+        let condvar = Arc::new(ConditionVariable::new(ticking_clock));
+
+        let mut guard = state.lock();
+        let notifier = {
+            let state = state.clone();
+            let condvar = condvar.clone();
+            std::thread::spawn(move || {
+                *state.lock() = true;
+                condvar.notify();
+            })
+        };
+
+        let mut result = WaitResult::Notified;
+        while !*guard {
+            result = condvar.wait_timeout(&mut guard, u64::MAX / 2);
+        }
+        assert_eq!(result, WaitResult::Notified);
+        assert!(state.is_locked());
+        drop(guard);
+        notifier.join().unwrap();
+    }
+
+    #[test]
+    fn wait_timeout_times_out_and_reacquires() {
+        let state = Mutex::new(7u32);
+        let condvar = ConditionVariable::new(ticking_clock);
+
+        let mut guard = state.lock();
+        assert_eq!(condvar.wait_timeout(&mut guard, 50), WaitResult::TimedOut);
+        assert!(state.is_locked());
+        *guard += 1;
+        drop(guard);
+        assert_eq!(*state.lock(), 8);
+    }
+
+    #[test]
+    fn deadline_miss_without_callback_still_counts() {
+        let scheduler = RtEdfScheduler::new();
+        scheduler.add_task(RtTask::new(7, 100, 0, 1, 100));
+        assert!(scheduler.dispatch_next(500).is_some());
+        assert_eq!(scheduler.get_sla_metrics().deadline_missed, 1);
+        assert!(scheduler.dispatch_next(500).is_none());
+    }
+}
\ No newline at end of file
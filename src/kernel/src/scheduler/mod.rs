@@ -4,7 +4,7 @@ pub mod edf_fast;
 pub mod preemption;
 pub mod preemption_advanced;
 
-pub use edf::{RtTask, RtEdfScheduler, SlaMetrics, DynamicPriorityManager, ConditionVariable};
+pub use edf::{RtTask, RtEdfScheduler, SlaMetrics, DynamicPriorityManager, ConditionVariable, WaitResult};
 pub use edf_fast::{FastRtTask, FastEdfScheduler, FastSlaMetrics};
 pub use preemption::{PreemptionContext, ContextSwitchTracker};
 pub use preemption_advanced::{TimeBudget, PreemptionDeadline, AdvancedPreemptionContext, TaskSla};
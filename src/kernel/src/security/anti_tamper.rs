@@ -1,10 +1,82 @@
 
+extern crate alloc;
+use alloc::vec::Vec;
 use core::sync::atomic::{AtomicBool, Ordering};
+use parking_lot::Mutex;
 use crate::security::secure_element::{ThreadManager, ThreadId};
 use crate::memory::MEMORY_DRIVER;
 use crate::security::trusted_execution::TrustedExecution;
 
 static TAMPER_DETECTED: AtomicBool = AtomicBool::new(false);
+static TAMPER_LOG: Mutex<TamperLog> = Mutex::new(TamperLog::new());
+
+pub const TAMPER_LOG_CAPACITY: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TamperEvent {
+    VoltageGlitch,
+    ClockGlitch,
+    CaseOpen,
+    DebugPortProbe,
+    MemoryRemap,
+}
+
+/// Fixed-size tamper timeline. Once full, the oldest event is overwritten
+/// and counted in `overwritten()`.
+pub struct TamperLog {
+    events: [Option<TamperEvent>; TAMPER_LOG_CAPACITY],
+    head: usize,
+    len: usize,
+    overwritten: u32,
+}
+
+impl TamperLog {
+    pub const fn new() -> Self {
+        TamperLog {
+            events: [None; TAMPER_LOG_CAPACITY],
+            head: 0,
+            len: 0,
+            overwritten: 0,
+        }
+    }
+
+    pub fn record_tamper(&mut self, event: TamperEvent) {
+        let tail = (self.head + self.len) % TAMPER_LOG_CAPACITY;
+        self.events[tail] = Some(event);
+        if self.len == TAMPER_LOG_CAPACITY {
+            self.head = (self.head + 1) % TAMPER_LOG_CAPACITY;
+            self.overwritten = self.overwritten.saturating_add(1);
+        } else {
+            self.len += 1;
+        }
+    }
+
+    /// Returns the buffered events oldest first and empties the log.
+    pub fn drain_events(&mut self) -> Vec<TamperEvent> {
+        let mut drained = Vec::with_capacity(self.len);
+        for i in 0..self.len {
+            let idx = (self.head + i) % TAMPER_LOG_CAPACITY;
+            if let Some(event) = self.events[idx].take() {
+                drained.push(event);
+            }
+        }
+        self.head = 0;
+        self.len = 0;
+        drained
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn overwritten(&self) -> u32 {
+        self.overwritten
+    }
+}
 
 pub struct AntiTamper;
 
@@ -15,20 +87,31 @@ impl AntiTamper {
         }
 
         if MEMORY_DRIVER.used() > MEMORY_DRIVER.total() {
+            Self::record_tamper(TamperEvent::MemoryRemap);
             Self::trigger_tamper("Memory usage anomaly");
         }
 
-        if Self::hardware_tamper_detected() {
+        if let Some(event) = Self::hardware_tamper_event() {
+            Self::record_tamper(event);
             Self::trigger_tamper("Hardware tampering detected");
         }
 
         if Self::software_tamper_detected() {
+            Self::record_tamper(TamperEvent::DebugPortProbe);
             Self::trigger_tamper("Software tampering detected");
         }
     }
 
-    fn hardware_tamper_detected() -> bool {
-        false
+    pub fn record_tamper(event: TamperEvent) {
+        TAMPER_LOG.lock().record_tamper(event);
+    }
+
+    pub fn drain_events() -> Vec<TamperEvent> {
+        TAMPER_LOG.lock().drain_events()
+    }
+
+    fn hardware_tamper_event() -> Option<TamperEvent> {
+        None
     }
 
     fn software_tamper_detected() -> bool {
@@ -64,4 +147,49 @@ impl TrustedExecution {
     pub fn execute_block_all() -> Result<(), &'static str> {
         Ok(())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drains_events_in_order() {
+        let mut log = TamperLog::new();
+        log.record_tamper(TamperEvent::VoltageGlitch);
+        log.record_tamper(TamperEvent::CaseOpen);
+        log.record_tamper(TamperEvent::MemoryRemap);
+
+        assert_eq!(log.len(), 3);
+        assert_eq!(
+            log.drain_events(),
+            [TamperEvent::VoltageGlitch, TamperEvent::CaseOpen, TamperEvent::MemoryRemap]
+        );
+        assert!(log.is_empty());
+        assert!(log.drain_events().is_empty());
+    }
+
+    #[test]
+    fn wraps_at_capacity() {
+        let mut log = TamperLog::new();
+        log.record_tamper(TamperEvent::ClockGlitch);
+        log.record_tamper(TamperEvent::ClockGlitch);
+        for _ in 0..TAMPER_LOG_CAPACITY - 1 {
+            log.record_tamper(TamperEvent::DebugPortProbe);
+        }
+        log.record_tamper(TamperEvent::CaseOpen);
+
+        assert_eq!(log.len(), TAMPER_LOG_CAPACITY);
+        assert_eq!(log.overwritten(), 2);
+        let events = log.drain_events();
+        assert_eq!(events.len(), TAMPER_LOG_CAPACITY);
+        assert_eq!(events[0], TamperEvent::DebugPortProbe);
+        assert_eq!(events[TAMPER_LOG_CAPACITY - 1], TamperEvent::CaseOpen);
+    }
+
+    #[test]
+    fn global_log_records_and_drains() {
+        AntiTamper::record_tamper(TamperEvent::ClockGlitch);
+        assert!(AntiTamper::drain_events().contains(&TamperEvent::ClockGlitch));
+    }
 }
\ No newline at end of file
@@ -10,5 +10,5 @@ pub use integrity::*;
 pub use secure_boot::*;
 pub use verified_boot::*;
 
-pub use secure_element::{ThreadId, ThreadManager, SecureElement, MemoryRegion, MemoryDriver};
+pub use secure_element::{ThreadId, ThreadManager, SecureElement, MemoryRegion, MemoryDriver, MemoryError};
 pub use trusted_execution::TrustedExecution;
@@ -8,6 +8,7 @@ use core::ptr;
 pub struct BootToken {
     pub token: [u8; 32],
     pub component_mask: u32,
+    pub image_version: u32,
 }
 
 #[repr(C)]
@@ -17,15 +18,53 @@ pub struct BootRegion {
     pub token: [u8; 32],
     pub component_mask: u32,
     pub checksum: u32,
+    pub image_version: u32,
 }
 
 const BOOT_REGION_BASE: usize = 0xFFF0_0000;
 const BOOT_MAGIC: u32 = 0xB007_B007;
 const BOOT_REGION_SIZE: usize = 512;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootError {
+    Rollback { image_version: u32, min_allowed: u32 },
+    Storage(&'static str),
+}
+
+impl From<BootError> for &'static str {
+    fn from(err: BootError) -> Self {
+        match err {
+            BootError::Rollback { .. } => "Secure Boot Failed: Image older than rollback floor",
+            BootError::Storage(msg) => msg,
+        }
+    }
+}
+
 pub struct SecureBoot;
 
 impl SecureBoot {
+    pub fn min_allowed_version(secure_element: &SecureElement) -> Result<u32, BootError> {
+        secure_element.read_monotonic_counter().map_err(BootError::Storage)
+    }
+
+    /// Rejects images older than the floor kept in the secure element's
+    /// monotonic counter, preventing downgrades to vulnerable builds.
+    pub fn verify_version(secure_element: &SecureElement, image_version: u32) -> Result<(), BootError> {
+        let min_allowed = Self::min_allowed_version(secure_element)?;
+        if image_version < min_allowed {
+            return Err(BootError::Rollback { image_version, min_allowed });
+        }
+        Ok(())
+    }
+
+    /// Raises the floor to `image_version`; call only after the image booted successfully.
+    pub fn commit_version(secure_element: &SecureElement, image_version: u32) -> Result<(), BootError> {
+        Self::verify_version(secure_element, image_version)?;
+        secure_element
+            .advance_monotonic_counter(image_version)
+            .map_err(BootError::Storage)
+    }
+
     pub fn boot_from_region(secure_element: &SecureElement, thread_manager: &mut ThreadManager) -> Result<(), &'static str> {
         let boot_token = Self::read_boot_region()?;
 
@@ -33,11 +72,14 @@ impl SecureBoot {
             return Err("Secure Boot Failed: Corrupted boot region");
         }
 
+        Self::verify_version(secure_element, boot_token.image_version)?;
 
         Self::enable_components(boot_token.component_mask, thread_manager)?;
 
         Self::zeroize_boot_region()?;
 
+        Self::commit_version(secure_element, boot_token.image_version)?;
+
         Ok(())
     }
 
@@ -62,12 +104,13 @@ impl SecureBoot {
             Ok(BootToken {
                 token: region.token,
                 component_mask: region.component_mask,
+                image_version: region.image_version,
             })
         }
     }
 
     fn verify_boot_region_integrity(token: &BootToken) -> bool {
-        let mut sum: u32 = token.component_mask;
+        let mut sum: u32 = token.component_mask.wrapping_add(token.image_version);
         for &byte in token.token.iter() {
             sum = sum.wrapping_add(byte as u32);
         }
@@ -127,3 +170,90 @@ impl SecureBoot {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::secure_element::SecureElementHardware;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    struct CounterOnlyElement {
+        counter: AtomicU32,
+    }
+
+    impl SecureElementHardware for CounterOnlyElement {
+        fn sign(&self, _: &[u8]) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn verify(&self, _: &[u8], _: &[u8], _: &[u8]) -> Result<bool, &'static str> { Err("unsupported") }
+        fn seal(&self, _: &[u8], _: &[u8]) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn unseal(&self, _: &[u8], _: &[u8]) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn derive_key(&self, _: &str, _: usize) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn attest(&self, _: &[u8]) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn generate_nonce(&self, _: usize) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn destroy_master_key(&self) -> Result<(), &'static str> { Ok(()) }
+
+        fn read_monotonic_counter(&self) -> Result<u32, &'static str> {
+            Ok(self.counter.load(Ordering::SeqCst))
+        }
+
+        fn advance_monotonic_counter(&self, value: u32) -> Result<(), &'static str> {
+            self.counter.fetch_max(value, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    struct NoCounterElement;
+
+    impl SecureElementHardware for NoCounterElement {
+        fn sign(&self, _: &[u8]) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn verify(&self, _: &[u8], _: &[u8], _: &[u8]) -> Result<bool, &'static str> { Err("unsupported") }
+        fn seal(&self, _: &[u8], _: &[u8]) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn unseal(&self, _: &[u8], _: &[u8]) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn derive_key(&self, _: &str, _: usize) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn attest(&self, _: &[u8]) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn generate_nonce(&self, _: usize) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn destroy_master_key(&self) -> Result<(), &'static str> { Ok(()) }
+    }
+
+    fn element_with_floor(floor: u32) -> SecureElement {
+        let hardware = Box::leak(Box::new(CounterOnlyElement { counter: AtomicU32::new(floor) }));
+        SecureElement::with_hardware(hardware)
+    }
+
+    #[test]
+    fn equal_or_newer_version_boots() {
+        let se = element_with_floor(5);
+        assert_eq!(SecureBoot::verify_version(&se, 5), Ok(()));
+        assert_eq!(SecureBoot::verify_version(&se, 6), Ok(()));
+    }
+
+    #[test]
+    fn older_version_is_rejected() {
+        let se = element_with_floor(5);
+        assert_eq!(
+            SecureBoot::verify_version(&se, 4),
+            Err(BootError::Rollback { image_version: 4, min_allowed: 5 })
+        );
+    }
+
+    #[test]
+    fn commit_advances_floor() {
+        let se = element_with_floor(3);
+        SecureBoot::commit_version(&se, 7).unwrap();
+        assert_eq!(SecureBoot::min_allowed_version(&se), Ok(7));
+        assert!(SecureBoot::verify_version(&se, 6).is_err());
+        assert!(SecureBoot::commit_version(&se, 6).is_err());
+        assert_eq!(SecureBoot::min_allowed_version(&se), Ok(7));
+    }
+
+    #[test]
+    fn missing_counter_fails_closed() {
+        let se = SecureElement::with_hardware(Box::leak(Box::new(NoCounterElement)));
+        let err = SecureBoot::verify_version(&se, 1).unwrap_err();
+        assert!(matches!(err, BootError::Storage(_)));
+        assert!(SecureBoot::commit_version(&se, 1).is_err());
+        let msg: &'static str = BootError::Rollback { image_version: 1, min_allowed: 2 }.into();
+        assert!(msg.contains("rollback"));
+    }
+}
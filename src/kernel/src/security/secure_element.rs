@@ -1,6 +1,6 @@
 extern crate alloc;
 use alloc::vec::Vec;
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use aes_gcm::Nonce;
 use aes_gcm::aead::{Aead, Payload};
 use aes_gcm::KeyInit;
@@ -10,6 +10,8 @@ use sha2::{Digest, Sha256};
 use hex;
 use core::num::NonZeroU32;
 use once_cell::sync::Lazy;
+use core::ptr::{self, NonNull};
+use rand_core::{RngCore, OsRng};
 
 include!(concat!(env!("OUT_DIR"), "/config.rs"));
 
@@ -29,8 +31,22 @@ pub trait SecureElementHardware: Send + Sync {
     fn generate_nonce(&self, length: usize) -> Result<Vec<u8>, &'static str>;
     
     fn destroy_master_key(&self) -> Result<(), &'static str>;
+
+    /// Backends without a rollback counter fail closed, so secure boot refuses
+    /// to run rather than silently skipping the version check.
+    fn read_monotonic_counter(&self) -> Result<u32, &'static str> {
+        Err("Monotonic counter not supported")
+    }
+
+    /// Raises the monotonic counter to `value`. Lower values are ignored: the
+    /// counter can never move backwards.
+    fn advance_monotonic_counter(&self, _value: u32) -> Result<(), &'static str> {
+        Err("Monotonic counter not supported")
+    }
 }
 
+static SOFTWARE_MONOTONIC_COUNTER: AtomicU32 = AtomicU32::new(0);
+
 pub struct SoftwareSecureElementStub;
 
 impl SecureElementHardware for SoftwareSecureElementStub {
@@ -113,6 +129,15 @@ impl SecureElementHardware for SoftwareSecureElementStub {
     fn destroy_master_key(&self) -> Result<(), &'static str> {
         Ok(())
     }
+
+    fn read_monotonic_counter(&self) -> Result<u32, &'static str> {
+        Ok(SOFTWARE_MONOTONIC_COUNTER.load(Ordering::SeqCst))
+    }
+
+    fn advance_monotonic_counter(&self, value: u32) -> Result<(), &'static str> {
+        SOFTWARE_MONOTONIC_COUNTER.fetch_max(value, Ordering::SeqCst);
+        Ok(())
+    }
 }
 
 impl SoftwareSecureElementStub {
@@ -150,6 +175,7 @@ mod hw {
     pub const REG_NONCE: usize = 0x14;
     pub const REG_STATUS: usize = 0x18;
     pub const REG_LOCK: usize = 0x1C;
+    pub const REG_MONOTONIC: usize = 0x20;
     
     pub unsafe fn read_reg(offset: usize) -> u32 {
         let addr = (SE_BASE + offset) as *const u32;
@@ -271,6 +297,24 @@ impl SecureElementHardware for HardwareSecureElementAdapter {
         }
         Ok(())
     }
+
+    fn read_monotonic_counter(&self) -> Result<u32, &'static str> {
+        if !hw::is_ready() {
+            return Err("Hardware SE not ready");
+        }
+        Ok(unsafe { hw::read_reg(hw::REG_MONOTONIC) })
+    }
+
+    fn advance_monotonic_counter(&self, value: u32) -> Result<(), &'static str> {
+        if !hw::is_ready() {
+            return Err("Hardware SE not ready");
+        }
+        let current = unsafe { hw::read_reg(hw::REG_MONOTONIC) };
+        if value > current {
+            unsafe { hw::write_reg(hw::REG_MONOTONIC, value); }
+        }
+        Ok(())
+    }
 }
 
 impl HardwareSecureElementAdapter {
@@ -294,10 +338,80 @@ pub enum ThreadId {
     User(u32),
 }
 
-pub struct ThreadManager;
+struct SecureThread {
+    id: ThreadId,
+    canary: u64,
+    /// Lowest word of the thread's stack. Stacks grow downward, so this is the
+    /// guard end an overflow reaches first.
+    guard: NonNull<u64>,
+}
+
+// `guard` points into a stack owned by the thread for the manager's lifetime.
+unsafe impl Send for SecureThread {}
+
+pub struct ThreadManager {
+    threads: parking_lot::Mutex<Vec<SecureThread>>,
+}
+
 impl ThreadManager {
+    pub const fn new() -> Self {
+        ThreadManager {
+            threads: parking_lot::Mutex::new(Vec::new()),
+        }
+    }
+
     pub fn get_current() -> ThreadId { ThreadId::Kernel }
     pub fn is_thread_active(&self, _thread_id: ThreadId) -> bool { true }
+
+    /// Registers `stack` as the stack of thread `id` and writes a fresh random
+    /// canary into its lowest word.
+    pub fn create_thread(&self, id: ThreadId, stack: &'static mut [u64]) -> Result<(), &'static str> {
+        let mut threads = self.threads.lock();
+        if threads.iter().any(|t| t.id == id) {
+            return Err("Thread already exists");
+        }
+        let guard = NonNull::from(stack.first_mut().ok_or("Thread stack is empty")?);
+        let canary = Self::random_canary()?;
+        unsafe { ptr::write_volatile(guard.as_ptr(), canary) };
+        threads.push(SecureThread { id, canary, guard });
+        Ok(())
+    }
+
+    pub fn destroy_thread(&self, id: ThreadId) -> bool {
+        let mut threads = self.threads.lock();
+        let before = threads.len();
+        threads.retain(|t| t.id != id);
+        threads.len() != before
+    }
+
+    pub fn thread_count(&self) -> usize {
+        self.threads.lock().len()
+    }
+
+    /// Returns every thread whose stack canary no longer matches the value
+    /// written at creation, for the watchdog to escalate.
+    pub fn check_canaries(&self) -> Vec<ThreadId> {
+        self.threads
+            .lock()
+            .iter()
+            .filter(|t| unsafe { ptr::read_volatile(t.guard.as_ptr()) } != t.canary)
+            .map(|t| t.id)
+            .collect()
+    }
+
+    fn random_canary() -> Result<u64, &'static str> {
+        let mut bytes = [0u8; 8];
+        OsRng.try_fill_bytes(&mut bytes).map_err(|_| "Entropy source unavailable")?;
+        // A zero canary would survive a stack cleared by an overflow.
+        Ok(u64::from_le_bytes(bytes) | 1)
+    }
+
+    #[cfg(test)]
+    fn overflow_into_guard(&self, id: ThreadId) {
+        if let Some(t) = self.threads.lock().iter().find(|t| t.id == id) {
+            unsafe { ptr::write_volatile(t.guard.as_ptr(), 0) };
+        }
+    }
 }
 
 pub struct SecureElement {
@@ -348,6 +462,14 @@ impl SecureElement {
         self.hardware.destroy_master_key()
     }
 
+    pub fn read_monotonic_counter(&self) -> Result<u32, &'static str> {
+        self.hardware.read_monotonic_counter()
+    }
+
+    pub fn advance_monotonic_counter(&self, value: u32) -> Result<(), &'static str> {
+        self.hardware.advance_monotonic_counter(value)
+    }
+
     pub fn verify_trusted_token(&self, token_hex: &str) -> bool {
         let key_source = if !MASTER_KEY.is_empty() { Some(MASTER_KEY.to_string()) } else { std::env::var("REDMI_MASTER_KEY").ok() };
         let key_bytes = match key_source {
@@ -407,14 +529,98 @@ impl SecureElement {
     }
 }
 
-pub struct MemoryRegion;
-pub struct MemoryDriver;
+const SECURE_POOL_BASE: usize = 0x8000_0000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryError {
+    Overlap,
+    InvalidRange,
+    NotMapped,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryRegion {
+    pub start: usize,
+    pub size: usize,
+    pub secure: bool,
+}
+
+impl MemoryRegion {
+    pub const fn new(start: usize, size: usize, secure: bool) -> Self {
+        MemoryRegion { start, size, secure }
+    }
+
+    /// Exclusive end address; regions are half-open `[start, end)`.
+    pub fn end(&self) -> Option<usize> {
+        self.start.checked_add(self.size)
+    }
+
+    pub fn overlaps(&self, other: &MemoryRegion) -> bool {
+        match (self.end(), other.end()) {
+            (Some(end), Some(other_end)) => self.start < other_end && other.start < end,
+            _ => true,
+        }
+    }
+}
+
+pub struct MemoryDriver {
+    mapped: parking_lot::Mutex<Vec<MemoryRegion>>,
+    next_base: parking_lot::Mutex<usize>,
+}
+
 impl MemoryDriver {
-    pub fn alloc(&self, size: usize, _secure: bool) -> Result<MemoryRegion, &'static str> { Ok(MemoryRegion) }
-    pub fn unprotect(&self, _region: &mut MemoryRegion) {}
-    pub fn free(&self, _region: &MemoryRegion) {}
+    pub const fn new() -> Self {
+        MemoryDriver {
+            mapped: parking_lot::Mutex::new(Vec::new()),
+            next_base: parking_lot::Mutex::new(SECURE_POOL_BASE),
+        }
+    }
+
+    pub fn alloc(&self, size: usize, secure: bool) -> Result<MemoryRegion, &'static str> {
+        let mut next_base = self.next_base.lock();
+        let region = MemoryRegion::new(*next_base, size, secure);
+        self.map_region(region).map_err(|_| "Secure region unavailable")?;
+        *next_base = region.end().ok_or("Secure pool exhausted")?;
+        Ok(region)
+    }
+
+    pub fn unprotect(&self, region: &mut MemoryRegion) {
+        region.secure = false;
+    }
+
+    pub fn free(&self, region: &MemoryRegion) {
+        let _ = self.unmap_region(region);
+    }
+
+    /// Maps `region`, refusing any range that intersects an existing mapping
+    /// so one secure allocation can never alias another. Adjacent is fine.
+    pub fn map_region(&self, region: MemoryRegion) -> Result<(), MemoryError> {
+        if region.size == 0 || region.end().is_none() {
+            return Err(MemoryError::InvalidRange);
+        }
+        let mut mapped = self.mapped.lock();
+        if mapped.iter().any(|existing| existing.overlaps(&region)) {
+            return Err(MemoryError::Overlap);
+        }
+        mapped.push(region);
+        Ok(())
+    }
+
+    pub fn unmap_region(&self, region: &MemoryRegion) -> Result<(), MemoryError> {
+        let mut mapped = self.mapped.lock();
+        let idx = mapped
+            .iter()
+            .position(|r| r.start == region.start && r.size == region.size)
+            .ok_or(MemoryError::NotMapped)?;
+        mapped.remove(idx);
+        Ok(())
+    }
+
+    pub fn regions(&self) -> Vec<MemoryRegion> {
+        self.mapped.lock().clone()
+    }
 }
-static MEMORY_DRIVER: MemoryDriver = MemoryDriver;
+static MEMORY_DRIVER: MemoryDriver = MemoryDriver::new();
 
 static ENCLAVE_ACTIVE: AtomicBool = AtomicBool::new(false);
 
@@ -477,4 +683,95 @@ impl TrustedExecution {
     fn thread_allowed(thread: ThreadId) -> bool {
         matches!(thread, ThreadId::Kernel | ThreadId::System)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::boxed::Box;
+
+    #[test]
+    fn adjacent_regions_are_allowed() {
+        let driver = MemoryDriver::new();
+        driver.map_region(MemoryRegion::new(0x1000, 0x1000, true)).unwrap();
+        driver.map_region(MemoryRegion::new(0x2000, 0x1000, true)).unwrap();
+        driver.map_region(MemoryRegion::new(0x0800, 0x0800, false)).unwrap();
+        assert_eq!(driver.regions().len(), 3);
+    }
+
+    #[test]
+    fn overlapping_regions_are_rejected() {
+        let driver = MemoryDriver::new();
+        driver.map_region(MemoryRegion::new(0x1000, 0x1000, true)).unwrap();
+
+        assert_eq!(driver.map_region(MemoryRegion::new(0x1800, 0x1000, true)), Err(MemoryError::Overlap));
+        assert_eq!(driver.map_region(MemoryRegion::new(0x0800, 0x0900, true)), Err(MemoryError::Overlap));
+        assert_eq!(driver.map_region(MemoryRegion::new(0x1200, 0x0100, true)), Err(MemoryError::Overlap));
+        assert_eq!(driver.map_region(MemoryRegion::new(0x0000, 0x4000, true)), Err(MemoryError::Overlap));
+        assert_eq!(driver.regions(), [MemoryRegion::new(0x1000, 0x1000, true)]);
+    }
+
+    #[test]
+    fn invalid_ranges_are_rejected() {
+        let driver = MemoryDriver::new();
+        assert_eq!(driver.map_region(MemoryRegion::new(0x1000, 0, true)), Err(MemoryError::InvalidRange));
+        assert_eq!(driver.map_region(MemoryRegion::new(usize::MAX, 2, true)), Err(MemoryError::InvalidRange));
+    }
+
+    #[test]
+    fn unmap_frees_the_range() {
+        let driver = MemoryDriver::new();
+        let region = MemoryRegion::new(0x4000, 0x100, true);
+        driver.map_region(region).unwrap();
+        driver.unmap_region(&region).unwrap();
+        assert!(driver.regions().is_empty());
+        assert_eq!(driver.unmap_region(&region), Err(MemoryError::NotMapped));
+        driver.map_region(MemoryRegion::new(0x4080, 0x100, true)).unwrap();
+    }
+
+    #[test]
+    fn alloc_hands_out_disjoint_regions() {
+        let driver = MemoryDriver::new();
+        let a = driver.alloc(0x100, true).unwrap();
+        let b = driver.alloc(0x200, true).unwrap();
+        assert!(!a.overlaps(&b));
+        driver.free(&a);
+        assert_eq!(driver.regions(), [b]);
+    }
+
+    #[test]
+    fn untouched_threads_pass_canary_check() {
+        let manager = ThreadManager::new();
+        manager.create_thread(ThreadId::System, leak_stack()).unwrap();
+        manager.create_thread(ThreadId::User(7), leak_stack()).unwrap();
+        assert_eq!(manager.thread_count(), 2);
+        assert!(manager.check_canaries().is_empty());
+        assert!(manager.create_thread(ThreadId::System, leak_stack()).is_err());
+        assert!(manager.create_thread(ThreadId::User(8), Box::leak(Box::new([0u64; 0]))).is_err());
+    }
+
+    #[test]
+    fn overflow_into_guard_word_is_reported() {
+        let manager = ThreadManager::new();
+        manager.create_thread(ThreadId::User(1), leak_stack()).unwrap();
+        manager.create_thread(ThreadId::User(2), leak_stack()).unwrap();
+
+        manager.overflow_into_guard(ThreadId::User(2));
+        assert_eq!(manager.check_canaries(), [ThreadId::User(2)]);
+
+        assert!(manager.destroy_thread(ThreadId::User(2)));
+        assert!(manager.check_canaries().is_empty());
+    }
+
+    #[test]
+    fn canaries_are_random_and_nonzero() {
+        let a = ThreadManager::random_canary().unwrap();
+        let b = ThreadManager::random_canary().unwrap();
+        assert_ne!(a, b);
+        assert_eq!(a & 1, 1);
+    }
+
+    fn leak_stack() -> &'static mut [u64] {
+        Box::leak(vec![0u64; 512].into_boxed_slice())
+    }
+}
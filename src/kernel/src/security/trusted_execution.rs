@@ -1,6 +1,67 @@
-pub struct TrustedExecution;
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+
+use crate::security::secure_element::SecureElement;
+
+const ATTESTATION_DOMAIN: &[u8] = b"TEE-MEASURED-CALL-v1";
+
+pub type TeeEntry = fn(&[u8]) -> Vec<u8>;
+
+pub struct TrustedExecution {
+    entries: parking_lot::Mutex<BTreeMap<u32, TeeEntry>>,
+}
 
 impl TrustedExecution {
+    pub const fn new() -> Self {
+        TrustedExecution {
+            entries: parking_lot::Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn register_entry(&self, code_id: u32, entry: TeeEntry) -> Result<(), &'static str> {
+        let mut entries = self.entries.lock();
+        if entries.contains_key(&code_id) {
+            return Err("TEE entry already registered");
+        }
+        entries.insert(code_id, entry);
+        Ok(())
+    }
+
+    pub fn has_entry(&self, code_id: u32) -> bool {
+        self.entries.lock().contains_key(&code_id)
+    }
+
+    /// Runs the TEE entry for `code_id` and returns its output together with
+    /// the secure element's keyed attestation over the call's measurement, so
+    /// only a holder of the device key can produce a valid one.
+    pub fn enter_measured(
+        &self,
+        code_id: u32,
+        input: &[u8],
+        secure_element: &SecureElement,
+    ) -> Result<(Vec<u8>, Vec<u8>), &'static str> {
+        let entry = self.entries.lock().get(&code_id).copied().ok_or("Unknown TEE entry")?;
+        let output = entry(input);
+        let attestation = secure_element.attest(&Self::measurement(code_id, input, &output))?;
+        Ok((output, attestation))
+    }
+
+    /// Unkeyed digest binding the code id, the input and the output. This is
+    /// what gets attested; on its own it proves nothing.
+    pub fn measurement(code_id: u32, input: &[u8], output: &[u8]) -> [u8; 32] {
+        let input_hash = Sha256::digest(input);
+        let output_hash = Sha256::digest(output);
+        let mut hasher = Sha256::new();
+        hasher.update(ATTESTATION_DOMAIN);
+        hasher.update(code_id.to_le_bytes());
+        hasher.update(input_hash);
+        hasher.update(output_hash);
+        hasher.finalize().into()
+    }
+
     pub fn execute_periodic(
         _token: &str,
         _thread_id: crate::security::secure_element::ThreadId,
@@ -13,4 +74,94 @@ impl TrustedExecution {
     ) -> Result<(), &'static str> {
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::secure_element::SecureElementHardware;
+    use alloc::boxed::Box;
+
+    /// Attests by hashing a per-device key in front of the challenge.
+    struct KeyedElement {
+        key: [u8; 16],
+    }
+
+    impl SecureElementHardware for KeyedElement {
+        fn sign(&self, _: &[u8]) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn verify(&self, _: &[u8], _: &[u8], _: &[u8]) -> Result<bool, &'static str> { Err("unsupported") }
+        fn seal(&self, _: &[u8], _: &[u8]) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn unseal(&self, _: &[u8], _: &[u8]) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn derive_key(&self, _: &str, _: usize) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn generate_nonce(&self, _: usize) -> Result<Vec<u8>, &'static str> { Err("unsupported") }
+        fn destroy_master_key(&self) -> Result<(), &'static str> { Ok(()) }
+
+        fn attest(&self, challenge: &[u8]) -> Result<Vec<u8>, &'static str> {
+            let mut hasher = Sha256::new();
+            hasher.update(self.key);
+            hasher.update(challenge);
+            Ok(hasher.finalize().to_vec())
+        }
+    }
+
+    fn device(key_byte: u8) -> SecureElement {
+        SecureElement::with_hardware(Box::leak(Box::new(KeyedElement { key: [key_byte; 16] })))
+    }
+
+    fn reverse(input: &[u8]) -> Vec<u8> {
+        input.iter().rev().copied().collect()
+    }
+
+    fn echo(input: &[u8]) -> Vec<u8> {
+        input.to_vec()
+    }
+
+    #[test]
+    fn identical_calls_attest_identically() {
+        let tee = TrustedExecution::new();
+        let se = device(1);
+        tee.register_entry(1, reverse).unwrap();
+
+        let (out_a, att_a) = tee.enter_measured(1, b"sign me", &se).unwrap();
+        let (out_b, att_b) = tee.enter_measured(1, b"sign me", &se).unwrap();
+        assert_eq!(out_a, b"em ngis");
+        assert_eq!(out_a, out_b);
+        assert_eq!(att_a, att_b);
+        assert_eq!(att_a, se.attest(&TrustedExecution::measurement(1, b"sign me", &out_a)).unwrap());
+    }
+
+    #[test]
+    fn attestation_binds_input_and_code_id() {
+        let tee = TrustedExecution::new();
+        let se = device(1);
+        tee.register_entry(1, echo).unwrap();
+        tee.register_entry(2, echo).unwrap();
+
+        let (_, base) = tee.enter_measured(1, b"payload", &se).unwrap();
+        let (_, other_input) = tee.enter_measured(1, b"payload!", &se).unwrap();
+        let (same_output, other_code) = tee.enter_measured(2, b"payload", &se).unwrap();
+        assert_eq!(same_output, b"payload");
+        assert_ne!(base, other_input);
+        assert_ne!(base, other_code);
+    }
+
+    #[test]
+    fn attestation_depends_on_device_key() {
+        let tee = TrustedExecution::new();
+        tee.register_entry(1, echo).unwrap();
+
+        let (output, genuine) = tee.enter_measured(1, b"payload", &device(1)).unwrap();
+        let (_, other_device) = tee.enter_measured(1, b"payload", &device(2)).unwrap();
+        assert_ne!(genuine, other_device);
+        assert_ne!(genuine, TrustedExecution::measurement(1, b"payload", &output));
+    }
+
+    #[test]
+    fn duplicate_and_unknown_entries_are_rejected() {
+        let tee = TrustedExecution::new();
+        tee.register_entry(3, echo).unwrap();
+        assert!(tee.register_entry(3, reverse).is_err());
+        assert!(tee.has_entry(3));
+        assert_eq!(tee.enter_measured(4, b"x", &device(1)), Err("Unknown TEE entry"));
+    }
+}
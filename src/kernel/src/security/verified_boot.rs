@@ -6,6 +6,7 @@ pub struct ThreadManager;
 pub struct ThreadState;
 use alloc::vec::Vec;
 use alloc::string::String;
+use sha2::{Digest, Sha256};
 
 #[derive(Debug, Clone)]
 pub struct ComponentSignature {
@@ -22,6 +23,71 @@ pub struct TrustChain {
     pub device_tree_sig: ComponentSignature,
 }
 
+pub const PCR_COUNT: usize = 24;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeasurementEvent {
+    pub pcr_index: u8,
+    pub digest: [u8; 32],
+    pub description: String,
+}
+
+/// TPM-style measurement log: each PCR only changes through
+/// `new = SHA256(old || digest)`, and every extension is kept in an
+/// append-only event log so a verifier can replay the boot chain.
+pub struct MeasurementLog {
+    pcrs: [[u8; 32]; PCR_COUNT],
+    events: Vec<MeasurementEvent>,
+}
+
+impl MeasurementLog {
+    pub fn new() -> Self {
+        MeasurementLog {
+            pcrs: [[0u8; 32]; PCR_COUNT],
+            events: Vec::new(),
+        }
+    }
+
+    pub fn extend(&mut self, pcr_index: u8, digest: [u8; 32], description: &str) -> Result<(), &'static str> {
+        let idx = pcr_index as usize;
+        if idx >= PCR_COUNT {
+            return Err("Invalid PCR index");
+        }
+        self.pcrs[idx] = Self::fold(&self.pcrs[idx], &digest);
+        self.events.push(MeasurementEvent {
+            pcr_index,
+            digest,
+            description: String::from(description),
+        });
+        Ok(())
+    }
+
+    pub fn pcr_value(&self, pcr_index: u8) -> Option<[u8; 32]> {
+        self.pcrs.get(pcr_index as usize).copied()
+    }
+
+    pub fn events(&self) -> &[MeasurementEvent] {
+        &self.events
+    }
+
+    /// Replays the event log from zeroed PCRs and checks it reproduces the current values.
+    pub fn verify_log(&self) -> bool {
+        let mut replay = [[0u8; 32]; PCR_COUNT];
+        for event in self.events.iter() {
+            let idx = event.pcr_index as usize;
+            replay[idx] = Self::fold(&replay[idx], &event.digest);
+        }
+        replay == self.pcrs
+    }
+
+    fn fold(old: &[u8; 32], digest: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(old);
+        hasher.update(digest);
+        hasher.finalize().into()
+    }
+}
+
 pub struct VerifiedBoot;
 
 impl VerifiedBoot {
@@ -114,4 +180,56 @@ impl VerifiedBoot {
     fn request_watchdog_reboot(reason: &str, timeout_ms: u64) {
         let _ = (reason, timeout_ms);
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(tag: u8) -> [u8; 32] {
+        [tag; 32]
+    }
+
+    #[test]
+    fn same_order_yields_same_pcr() {
+        let mut a = MeasurementLog::new();
+        let mut b = MeasurementLog::new();
+        for log in [&mut a, &mut b] {
+            log.extend(0, digest(1), "bootloader").unwrap();
+            log.extend(0, digest(2), "kernel").unwrap();
+            log.extend(7, digest(3), "device_tree").unwrap();
+        }
+
+        assert_eq!(a.pcr_value(0), b.pcr_value(0));
+        assert_eq!(a.pcr_value(7), b.pcr_value(7));
+        assert_ne!(a.pcr_value(0), Some([0u8; 32]));
+        assert_eq!(a.pcr_value(1), Some([0u8; 32]));
+        assert!(a.verify_log());
+    }
+
+    #[test]
+    fn different_order_yields_different_pcr() {
+        let mut a = MeasurementLog::new();
+        a.extend(0, digest(1), "bootloader").unwrap();
+        a.extend(0, digest(2), "kernel").unwrap();
+
+        let mut b = MeasurementLog::new();
+        b.extend(0, digest(2), "kernel").unwrap();
+        b.extend(0, digest(1), "bootloader").unwrap();
+
+        assert_ne!(a.pcr_value(0), b.pcr_value(0));
+    }
+
+    #[test]
+    fn event_log_is_append_only_record() {
+        let mut log = MeasurementLog::new();
+        log.extend(4, digest(9), "initramfs").unwrap();
+        assert!(log.extend(PCR_COUNT as u8, digest(9), "bad").is_err());
+
+        assert_eq!(log.events().len(), 1);
+        assert_eq!(log.events()[0].pcr_index, 4);
+        assert_eq!(log.events()[0].digest, digest(9));
+        assert_eq!(log.events()[0].description, "initramfs");
+        assert_eq!(log.pcr_value(PCR_COUNT as u8), None);
+    }
+}
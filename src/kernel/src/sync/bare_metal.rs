@@ -1,10 +1,19 @@
 
+use alloc::sync::Arc;
 use core::cell::UnsafeCell;
 use core::ops::{Deref, DerefMut};
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicU32, Ordering};
 
+use crate::scheduler::DynamicPriorityManager;
+
+const NO_OWNER: u32 = u32::MAX;
+const ANONYMOUS_OWNER: u32 = u32::MAX - 1;
+
+/// The lock word is the owner's task id (`NO_OWNER` when free), so the owner
+/// is published by the same CAS that acquires the lock.
 pub struct Mutex<T: ?Sized> {
-    lock: AtomicBool,
+    owner: AtomicU32,
+    pi: Option<Arc<DynamicPriorityManager>>,
     data: UnsafeCell<T>,
 }
 
@@ -14,29 +23,82 @@ unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
 impl<T> Mutex<T> {
     pub fn new(data: T) -> Self {
         Mutex {
-            lock: AtomicBool::new(false),
+            owner: AtomicU32::new(NO_OWNER),
+            pi: None,
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Priority-inheritance variant: a task blocking on the lock lends its
+    /// effective priority to the current holder until release. `lock` and
+    /// `try_lock` act on behalf of the manager's current task.
+    pub fn new_with_pi(data: T, manager: Arc<DynamicPriorityManager>) -> Self {
+        Mutex {
+            owner: AtomicU32::new(NO_OWNER),
+            pi: Some(manager),
             data: UnsafeCell::new(data),
         }
     }
 
     pub fn lock(&self) -> MutexGuard<'_, T> {
+        self.lock_as(self.implicit_owner())
+    }
+
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        self.try_lock_as(self.implicit_owner())
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    pub fn has_priority_inheritance(&self) -> bool {
+        self.pi.is_some()
+    }
+
+    pub fn owner(&self) -> Option<u32> {
+        self.pi.as_ref()?;
+        match self.owner.load(Ordering::Acquire) {
+            NO_OWNER | ANONYMOUS_OWNER => None,
+            id => Some(id),
+        }
+    }
+
+    pub fn lock_as(&self, task_id: u32) -> MutexGuard<'_, T> {
         loop {
-            match self.lock.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed) {
-                Ok(_) => break,
-                Err(_) => {
-                    core::hint::spin_loop();
-                }
+            if let Some(guard) = self.try_lock_as(task_id) {
+                return guard;
             }
+            core::hint::spin_loop();
         }
-        MutexGuard { mutex: self }
     }
 
-    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
-        match self.lock.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed) {
+    /// Acquires on behalf of `task_id`. On contention the holder inherits
+    /// the caller's effective priority until it releases this lock.
+    pub fn try_lock_as(&self, task_id: u32) -> Option<MutexGuard<'_, T>> {
+        match self.owner.compare_exchange(NO_OWNER, task_id, Ordering::Acquire, Ordering::Acquire) {
             Ok(_) => Some(MutexGuard { mutex: self }),
-            Err(_) => None,
+            Err(owner) => {
+                if let Some(manager) = &self.pi {
+                    if owner != task_id && owner != ANONYMOUS_OWNER {
+                        manager.inherit(self.key(), owner, manager.effective_priority(task_id));
+                        // The holder may have released between the CAS and the
+                        // inherit; don't leave a boost behind for it.
+                        if self.owner.load(Ordering::Acquire) != owner {
+                            manager.release(self.key(), owner);
+                        }
+                    }
+                }
+                None
+            }
         }
     }
+
+    fn implicit_owner(&self) -> u32 {
+        self.pi.as_ref().map_or(ANONYMOUS_OWNER, |manager| manager.current_task())
+    }
+
+    fn key(&self) -> usize {
+        self as *const Self as *const () as usize
+    }
 }
 
 pub struct MutexGuard<'a, T: ?Sized> {
@@ -59,6 +121,105 @@ impl<'a, T: ?Sized> DerefMut for MutexGuard<'a, T> {
 
 impl<'a, T: ?Sized> Drop for MutexGuard<'a, T> {
     fn drop(&mut self) {
-        self.mutex.lock.store(false, Ordering::Release);
+        if let Some(manager) = &self.mutex.pi {
+            let owner = self.mutex.owner.load(Ordering::Relaxed);
+            manager.release(self.mutex.key(), owner);
+        }
+        self.mutex.owner.store(NO_OWNER, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::Priority;
+
+    const LOW: u32 = 1;
+    const MEDIUM: u32 = 2;
+    const HIGH: u32 = 3;
+
+    fn manager() -> Arc<DynamicPriorityManager> {
+        let manager = Arc::new(DynamicPriorityManager::new());
+        manager.register(LOW, Priority::Low);
+        manager.register(MEDIUM, Priority::Normal);
+        manager.register(HIGH, Priority::High);
+        manager
+    }
+
+    #[test]
+    fn holder_inherits_waiter_priority_until_release() {
+        let manager = manager();
+        let mutex = Mutex::new_with_pi(0u32, manager.clone());
+
+        let guard = mutex.try_lock_as(LOW).unwrap();
+        assert_eq!(mutex.owner(), Some(LOW));
+        assert_eq!(manager.effective_priority(LOW), Priority::Low);
+
+        assert!(mutex.try_lock_as(HIGH).is_none());
+        assert_eq!(manager.effective_priority(LOW), Priority::High);
+        assert!(manager.effective_priority(LOW) > manager.effective_priority(MEDIUM));
+
+        drop(guard);
+        assert_eq!(manager.effective_priority(LOW), Priority::Low);
+        assert_eq!(mutex.owner(), None);
+
+        let guard = mutex.try_lock_as(HIGH).unwrap();
+        assert_eq!(mutex.owner(), Some(HIGH));
+        drop(guard);
+    }
+
+    #[test]
+    fn lower_priority_waiter_does_not_boost() {
+        let manager = manager();
+        let mutex = Mutex::new_with_pi((), manager.clone());
+
+        let _guard = mutex.try_lock_as(MEDIUM).unwrap();
+        assert!(mutex.try_lock_as(LOW).is_none());
+        assert_eq!(manager.effective_priority(MEDIUM), Priority::Normal);
+        assert!(!manager.is_boosted(MEDIUM));
+    }
+
+    #[test]
+    fn releasing_one_lock_keeps_boost_from_another() {
+        let manager = manager();
+        let first = Mutex::new_with_pi((), manager.clone());
+        let second = Mutex::new_with_pi((), manager.clone());
+
+        let outer = first.try_lock_as(LOW).unwrap();
+        let inner = second.try_lock_as(LOW).unwrap();
+        assert!(second.try_lock_as(MEDIUM).is_none());
+        assert!(first.try_lock_as(HIGH).is_none());
+        assert_eq!(manager.effective_priority(LOW), Priority::High);
+
+        drop(outer);
+        assert_eq!(manager.effective_priority(LOW), Priority::Normal);
+        drop(inner);
+        assert_eq!(manager.effective_priority(LOW), Priority::Low);
+    }
+
+    #[test]
+    fn plain_lock_records_current_task() {
+        let manager = manager();
+        let mutex = Mutex::new_with_pi(0u32, manager.clone());
+
+        manager.set_current_task(LOW);
+        let guard = mutex.lock();
+        assert_eq!(mutex.owner(), Some(LOW));
+
+        manager.set_current_task(HIGH);
+        assert!(mutex.try_lock().is_none());
+        assert_eq!(manager.effective_priority(LOW), Priority::High);
+
+        drop(guard);
+        assert!(!manager.is_boosted(LOW));
+        assert_eq!(*mutex.try_lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn plain_mutex_ignores_priorities() {
+        let mutex = Mutex::new(5u32);
+        assert!(!mutex.has_priority_inheritance());
+        let _guard = mutex.try_lock_as(LOW).unwrap();
+        assert_eq!(mutex.owner(), None);
     }
 }
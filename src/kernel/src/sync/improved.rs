@@ -2,6 +2,7 @@
 use core::sync::atomic::{AtomicU32, Ordering};
 use alloc::vec::Vec;
 use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
 pub use parking_lot::{Mutex, RwLock, Once, Condvar};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -40,9 +41,19 @@ impl Task {
     }
 }
 
+/// Runtime charged by `pick_next` for one scheduling slot.
+pub const FAIR_SLICE_US: u64 = 1_000;
+
+#[derive(Debug, Clone, Copy)]
+struct FairShare {
+    weight: u32,
+    vruntime: u64,
+}
+
 pub struct FairScheduler {
     tasks: parking_lot::Mutex<Vec<Task>>,
     current_task: AtomicU32,
+    shares: parking_lot::Mutex<BTreeMap<u32, FairShare>>,
 }
 
 impl FairScheduler {
@@ -50,15 +61,69 @@ impl FairScheduler {
         FairScheduler {
             tasks: parking_lot::Mutex::new(Vec::new()),
             current_task: AtomicU32::new(0),
+            shares: parking_lot::Mutex::new(BTreeMap::new()),
         }
     }
 
     pub fn add_task(&self, task: Task) {
+        {
+            // Newcomers start at the current minimum so they neither starve
+            // nor monopolise the CPU against long-running tasks.
+            let mut shares = self.shares.lock();
+            let floor = shares.values().map(|s| s.vruntime).min().unwrap_or(0);
+            shares.insert(task.id, FairShare {
+                weight: task.priority as u32,
+                vruntime: floor,
+            });
+        }
         let mut tasks = self.tasks.lock();
         tasks.push(task);
         tasks.sort_by(|a, b| b.priority.cmp(&a.priority));
     }
 
+    pub fn set_weight(&self, task_id: u32, weight: u32) -> Result<(), &'static str> {
+        if weight == 0 {
+            return Err("Weight must be non-zero");
+        }
+        match self.shares.lock().get_mut(&task_id) {
+            Some(share) => {
+                share.weight = weight;
+                Ok(())
+            }
+            None => Err("Unknown task"),
+        }
+    }
+
+    pub fn weight(&self, task_id: u32) -> Option<u32> {
+        self.shares.lock().get(&task_id).map(|s| s.weight)
+    }
+
+    pub fn virtual_runtime(&self, task_id: u32) -> Option<u64> {
+        self.shares.lock().get(&task_id).map(|s| s.vruntime)
+    }
+
+    /// Charges `runtime_us` of CPU time to `task_id`, scaled down by its weight.
+    pub fn account_runtime(&self, task_id: u32, runtime_us: u64) {
+        if let Some(share) = self.shares.lock().get_mut(&task_id) {
+            share.vruntime = share
+                .vruntime
+                .saturating_add(runtime_us.saturating_mul(1024) / share.weight as u64);
+        }
+    }
+
+    /// Weighted fair pick: the task with the lowest virtual runtime runs next
+    /// and is charged one `FAIR_SLICE_US` slot. Ties go to the lowest id.
+    pub fn pick_next(&self) -> Option<u32> {
+        let task_id = self
+            .shares
+            .lock()
+            .iter()
+            .min_by_key(|(id, share)| (share.vruntime, **id))
+            .map(|(id, _)| *id)?;
+        self.account_runtime(task_id, FAIR_SLICE_US);
+        Some(task_id)
+    }
+
     pub fn schedule_next(&self) -> Option<u32> {
         let tasks = self.tasks.lock();
         if tasks.is_empty() {
@@ -166,6 +231,39 @@ mod tests {
         assert_eq!(next, Some(3));
     }
 
+    #[test]
+    fn test_fair_share_follows_weights() {
+        let scheduler = FairScheduler::new();
+        scheduler.add_task(Task::new(1, Priority::Normal));
+        scheduler.add_task(Task::new(2, Priority::Normal));
+        scheduler.set_weight(1, 1).unwrap();
+        scheduler.set_weight(2, 3).unwrap();
+
+        let mut slots = [0u32; 2];
+        for _ in 0..400 {
+            let id = scheduler.pick_next().unwrap();
+            slots[(id - 1) as usize] += 1;
+        }
+
+        assert_eq!(slots[0] + slots[1], 400);
+        assert!((98..=102).contains(&slots[0]), "light task got {}", slots[0]);
+        assert!((298..=302).contains(&slots[1]), "heavy task got {}", slots[1]);
+        let v1 = scheduler.virtual_runtime(1).unwrap();
+        let v2 = scheduler.virtual_runtime(2).unwrap();
+        assert!(v1.abs_diff(v2) <= FAIR_SLICE_US * 1024);
+    }
+
+    #[test]
+    fn test_fair_share_rejects_bad_weight() {
+        let scheduler = FairScheduler::new();
+        scheduler.add_task(Task::new(1, Priority::High));
+        assert_eq!(scheduler.weight(1), Some(Priority::High as u32));
+        assert!(scheduler.set_weight(1, 0).is_err());
+        assert!(scheduler.set_weight(9, 2).is_err());
+        assert_eq!(scheduler.virtual_runtime(9), None);
+        assert_eq!(FairScheduler::new().pick_next(), None);
+    }
+
     #[test]
     fn test_interrupt_controller() {
         let controller = InterruptController::new();